@@ -0,0 +1,150 @@
+use crate::*;
+use near_sdk::ext_contract;
+
+pub trait NonFungibleTokenApproval {
+    //approve an account ID to transfer a token on your behalf
+    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>);
+
+    //check if the passed in account has access to approve the token ID
+    fn nft_is_approved_for_id(
+        &self,
+        token_id: TokenId,
+        approved_account_id: AccountId,
+        approval_id: Option<u64>,
+    ) -> bool;
+
+    //revoke a specific account from transferring the token on your behalf
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId);
+
+    //revoke all accounts from transferring the token on your behalf
+    fn nft_revoke_all(&mut self, token_id: TokenId);
+}
+
+//the approved contract is notified through nft_on_approve whenever a msg is passed to nft_approve
+#[ext_contract(ext_non_fungible_approval_receiver)]
+trait NonFungibleTokenApprovalsReceiver {
+    fn nft_on_approve(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        approval_id: u64,
+        msg: String,
+    );
+}
+
+#[near_bindgen]
+impl NonFungibleTokenApproval for Contract {
+    //allow a specific account ID to approve a token on your behalf
+    #[payable]
+    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>) {
+        //assert that the user attached at least 1 yoctoNEAR, for security reasons and to pay for the approval's storage
+        assert_at_least_one_yocto();
+
+        //get the token object from the token ID
+        let mut token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+
+        //make sure that the person calling the function is the owner of the token
+        if env::predecessor_account_id() != token.owner_id {
+            env::panic_str("ERR_NOT_OWNER");
+        }
+
+        //measure the storage used by the token before the approval is added
+        let initial_storage_usage = env::storage_usage();
+
+        //get the next approval ID and store the account with it
+        let approval_id: u64 = token.next_approval_id;
+        token.approved_account_ids.insert(account_id.clone(), approval_id);
+
+        //increment the token's next approval ID and insert the token back into the tokens_by_id collection
+        token.next_approval_id += 1;
+        self.token_by_id.insert(&token_id, &token);
+
+        //the owner pays for the storage the approval takes up, the surplus is refunded
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+
+        //if some message was passed into the function, we initiate a cross contract call on the
+        //account we're giving access to
+        if let Some(msg) = msg {
+            ext_non_fungible_approval_receiver::ext(account_id)
+                .nft_on_approve(token_id, token.owner_id, approval_id, msg)
+                .as_return();
+        }
+    }
+
+    //check if the passed in account has access to approve the token ID
+    fn nft_is_approved_for_id(
+        &self,
+        token_id: TokenId,
+        approved_account_id: AccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        //get the token object from the token_id
+        let token = match self.token_by_id.get(&token_id) {
+            Some(token) => token,
+            //unknown tokens have no approvals
+            None => return false,
+        };
+
+        //get the approval number for the passed in account ID
+        match token.approved_account_ids.get(&approved_account_id) {
+            //if an approval ID was passed in, it must match the stored one
+            Some(actual_approval_id) => approval_id
+                .map(|approval_id| approval_id == *actual_approval_id)
+                .unwrap_or(true),
+            None => false,
+        }
+    }
+
+    //revoke a specific account from transferring the token on your behalf
+    #[payable]
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+
+        //get the token object using the passed in token_id
+        let mut token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+
+        //make sure that the caller of the function is the owner of the token
+        if env::predecessor_account_id() != token.owner_id {
+            env::panic_str("ERR_NOT_OWNER");
+        }
+
+        //if the account ID was in the token's approval, we remove it, refund its storage and save the token
+        if token.approved_account_ids.remove(&account_id).is_some() {
+            refund_approved_account_ids_iter(env::predecessor_account_id(), [account_id].iter());
+            self.token_by_id.insert(&token_id, &token);
+        }
+    }
+
+    //revoke all accounts from transferring the token on your behalf
+    #[payable]
+    fn nft_revoke_all(&mut self, token_id: TokenId) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+
+        //get the token object from the passed in token ID
+        let mut token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+
+        //make sure the caller is the token owner
+        if env::predecessor_account_id() != token.owner_id {
+            env::panic_str("ERR_NOT_OWNER");
+        }
+
+        //only revoke if the approved account IDs for the token is not empty
+        if !token.approved_account_ids.is_empty() {
+            //refund the storage of every approval to the owner, who paid for it
+            refund_approved_account_ids(env::predecessor_account_id(), &token.approved_account_ids);
+            token.approved_account_ids.clear();
+            self.token_by_id.insert(&token_id, &token);
+        }
+    }
+}
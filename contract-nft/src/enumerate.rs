@@ -26,6 +26,16 @@ impl Contract {
         .collect()
     }
 
+    //Query for the token IDs on the contract using pagination, much lighter than nft_tokens
+    pub fn nft_token_ids(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<TokenId> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.token_metadata_by_id.keys()
+        .skip(start as usize)
+        .take(limit.unwrap_or(50) as usize)
+        .collect()
+    }
+
     //get the total supply of NFTs for a given owner
     pub fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
         //get the set of tokens for the passed in owner
@@ -76,4 +86,86 @@ impl Contract {
             //since we turned the keys into an iterator, we need to turn it back into a vector to return
             .collect()
     }
-}
\ No newline at end of file
+
+    //Query for the soulbound tokens on the contract (regardless of the owner) using pagination
+    pub fn nft_soulbound_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<JsonToken> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        //iterate through each token, keeping only the soulbound ones
+        self.token_metadata_by_id.keys()
+        .filter(|token_id| {
+            self.token_by_id
+                .get(token_id)
+                .map(|token| token.soulbound)
+                .unwrap_or(false)
+        })
+        //skip to the index we specified in the start variable
+        .skip(start as usize)
+        //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+        .take(limit.unwrap_or(50) as usize)
+        //we'll map the token IDs which are strings into Json Tokens
+        .map(|token_id| self.json_token(token_id.clone()).unwrap())
+        //turn the iterator back into a vector to return
+        .collect()
+    }
+
+    //Query for the tokens of a given type (score tier) using pagination
+    pub fn nft_tokens_by_type(
+        &self,
+        token_type: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        //if there are no tokens of that type, we'll simply return an empty vector
+        let tokens = match self.tokens_per_type.get(&token_type) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        tokens
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            //we'll map the token IDs which are strings into Json Tokens
+            .map(|token_id| self.json_token(token_id).unwrap())
+            //turn the iterator back into a vector to return
+            .collect()
+    }
+
+    //Query for the tokens attesting to a score within [min, max] using pagination
+    pub fn nft_tokens_in_score_range(
+        &self,
+        min: u16,
+        max: u16,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        if min > max {
+            env::panic_str("ERR_INVALID_RANGE");
+        }
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        //iterate through each token, keeping only the ones whose embedded score is in range
+        self.token_metadata_by_id.iter()
+        .filter(|(_, metadata)| {
+            score_from_metadata(metadata)
+                .map(|score| (min..=max).contains(&score))
+                .unwrap_or(false)
+        })
+        //skip to the index we specified in the start variable
+        .skip(start as usize)
+        //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+        .take(limit.unwrap_or(50) as usize)
+        //we'll map the token IDs which are strings into Json Tokens
+        .map(|(token_id, _)| self.json_token(token_id).unwrap())
+        //turn the iterator back into a vector to return
+        .collect()
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use near_sdk::env;
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::{
+    CONTRACT_EVENTS_VERSION, CONTRACT_STANDARD_NAME, NFT_EVENTS_VERSION, NFT_METADATA_UPDATE_VERSION,
+    NFT_STANDARD_NAME,
+};
+
+/*
+This .rs file defines the events emitted by the contract, following the NEP-297 standard.
+An event is logged as a string prefixed by 'EVENT_JSON:' and followed by the JSON serialization
+of an 'EventLog', so that indexers can pick it up from the transaction logs.
+ */
+
+//enum that represents the data type of the EventLog
+//the event name and its data are flattened into the EventLog as 'event' and 'data'
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+#[serde(crate = "near_sdk::serde")]
+#[non_exhaustive]
+pub enum EventLogVariant {
+    NftMint(Vec<NftMintLog>),
+    NftTransfer(Vec<NftTransferLog>),
+    NftBurn(Vec<NftBurnLog>),
+    NftMetadataUpdate(Vec<NftMetadataUpdateLog>),
+    MinterUpdate(Vec<MinterUpdateLog>),
+    TokenTypeLockUpdate(Vec<TokenTypeLockUpdateLog>),
+    ContractPauseUpdate(Vec<ContractPauseUpdateLog>),
+    ContractOwnerTransfer(Vec<ContractOwnerTransferLog>),
+    ScoreOracleUpdate(Vec<ScoreOracleUpdateLog>),
+}
+
+//interface to capture data about an event
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventLog {
+    //standard name, e.g. "nep171"
+    pub standard: String,
+    //version of the standard, e.g. "1.0.0"
+    pub version: String,
+
+    //the data related with the event stored in a vector
+    #[serde(flatten)]
+    pub event: EventLogVariant,
+}
+
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "EVENT_JSON:{}",
+            &near_sdk::serde_json::to_string(self).map_err(|_| fmt::Error)?
+        ))
+    }
+}
+
+impl EventLogVariant {
+    //the standard the event belongs to and its version: the NFT standard's events go out as "nep171",
+    //the admin events of the contract under its own standard name, so that indexers tell them apart
+    fn standard(&self) -> (&'static str, &'static str) {
+        match self {
+            EventLogVariant::NftMint(_) | EventLogVariant::NftTransfer(_) | EventLogVariant::NftBurn(_) => {
+                (NFT_STANDARD_NAME, NFT_EVENTS_VERSION)
+            }
+            EventLogVariant::NftMetadataUpdate(_) => (NFT_STANDARD_NAME, NFT_METADATA_UPDATE_VERSION),
+            _ => (CONTRACT_STANDARD_NAME, CONTRACT_EVENTS_VERSION),
+        }
+    }
+}
+
+//log an event under the standard it belongs to as a serialized json
+pub(crate) fn log_event(event: EventLogVariant) {
+    let (standard, version) = event.standard();
+    let log = EventLog {
+        standard: standard.to_string(),
+        version: version.to_string(),
+        event,
+    };
+    env::log_str(&log.to_string());
+}
+
+//an event log to capture tokens minting
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMintLog {
+    //owner of the minted tokens
+    pub owner_id: String,
+    //vector of the minted token IDs
+    pub token_ids: Vec<String>,
+
+    //an optional memo to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+//an event log to capture tokens changing hands
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftTransferLog {
+    //the approved account that transferred the tokens on behalf of their owner, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<String>,
+
+    //previous owner of the tokens
+    pub old_owner_id: String,
+    //new owner of the tokens
+    pub new_owner_id: String,
+    //vector of the transferred token IDs
+    pub token_ids: Vec<String>,
+
+    //an optional memo to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+//an event log to capture tokens burning
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnLog {
+    //owner of the burned tokens
+    pub owner_id: String,
+    //vector of the burned token IDs
+    pub token_ids: Vec<String>,
+
+    //an optional memo to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+//an event log to capture updates of token metadata
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMetadataUpdateLog {
+    //vector of the updated token IDs
+    pub token_ids: Vec<String>,
+
+    //an optional memo to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+//an event log to capture the owner granting or revoking the permission to mint
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MinterUpdateLog {
+    //the account whose permission changed
+    pub account_id: String,
+    //whether the account can mint from now on
+    pub authorized: bool,
+}
+
+//an event log to capture the owner freezing or resuming the minting of a token type (score tier)
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenTypeLockUpdateLog {
+    //the token type whose lock changed
+    pub token_type: String,
+    //whether minting of the token type is frozen from now on
+    pub locked: bool,
+}
+
+//an event log to capture the owner suspending or resuming minting and transfers
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractPauseUpdateLog {
+    //whether the contract is paused from now on
+    pub paused: bool,
+}
+
+//an event log to capture the contract changing owners
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractOwnerTransferLog {
+    //owner handing the contract over
+    pub old_owner_id: String,
+    //owner administering the contract from now on
+    pub new_owner_id: String,
+}
+
+//an event log to capture the owner setting the oracle mint_if_qualified queries
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreOracleUpdateLog {
+    //the oracle queried from now on
+    pub oracle: String,
+    //the lowest score a token gets minted for from now on
+    pub min_score: u16,
+}
@@ -2,6 +2,9 @@ use crate::*;
 use near_sdk::CryptoHash;
 use std::mem::size_of;
 
+//nanoseconds in a day, the width of the buckets mints are counted in
+const NS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+
 
 /*
 This .rs files contains all 'internal methods' for the contract, 
@@ -18,34 +21,34 @@ There are 2 types of methods:
 //         gasless methods         //
 // ------------------------------- //
 
-// //calcualtes how many bytes of storage is taken up by each approved account id
-// pub (crate) fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
-//     //The extra 4 bytes are coming from Borsh serialization to store the length of the string.
-//     account_id.as_str().len() as u64 + 4 + size_of::<u64>() as u64
-// }
-
-// //refund the cost for storage taken up by the approved account IDs saved under a given account
-// //and send the funds to the given account
-// pub (crate) fn refund_approved_account_ids_iter<'a, I>(
-//     account_id: AccountId,
-//     //the approved account IDs must be passed in as an iterator "I"
-//     approved_account_ids: I, 
-// ) -> Promise where I: Iterator<Item = &'a AccountId>,
-// {
-//     //get the storage total by going through and summing all the bytes for each approved account IDs
-//     let storage_released: u64 = approved_account_ids.map(bytes_for_approved_account_id).sum();
-//     //transfer into the account the storage that is released
-//     Promise::new(account_id).transfer(Balance::from(storage_released) * env::storage_byte_cost())
-// }
-
-// //takes a map of approved account IDs and refund the storage cost to a given account
-// pub (crate) fn refund_approved_account_ids(
-//     account_id: AccountId,
-//     approved_account_ids: &HashMap<AccountId, u64>,
-// ) -> Promise {
-//     //call the function "refund_Approved_account_ids_iter" and pass the approved account IDs as keys
-//     refund_approved_account_ids_iter(account_id, approved_account_ids.keys())
-// }
+//calculates how many bytes of storage is taken up by each approved account id
+pub(crate) fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
+    //The extra 4 bytes are coming from Borsh serialization to store the length of the string.
+    account_id.as_str().len() as u64 + 4 + size_of::<u64>() as u64
+}
+
+//refund the cost for storage taken up by the approved account IDs saved under a given account
+//and send the funds to the given account
+pub(crate) fn refund_approved_account_ids_iter<'a, I>(
+    account_id: AccountId,
+    //the approved account IDs must be passed in as an iterator "I"
+    approved_account_ids: I,
+) where I: Iterator<Item = &'a AccountId>,
+{
+    //get the storage total by going through and summing all the bytes for each approved account IDs
+    let storage_released: u64 = approved_account_ids.map(bytes_for_approved_account_id).sum();
+    //transfer into the account the storage that is released, tokens without approvals release none
+    refund_released_storage(account_id, storage_released);
+}
+
+//takes a map of approved account IDs and refund the storage cost to a given account
+pub(crate) fn refund_approved_account_ids(
+    account_id: AccountId,
+    approved_account_ids: &HashMap<AccountId, u64>,
+) {
+    //call the function "refund_approved_account_ids_iter" and pass the approved account IDs as keys
+    refund_approved_account_ids_iter(account_id, approved_account_ids.keys())
+}
 
 //used to generate a unique prefix in our storage collections (this is to avoid data collisions)
 pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
@@ -56,42 +59,102 @@ pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
     hash
 }
 
-// pub (crate) fn assert_one_yocto() {
-//     assert_eq!(env::attached_deposit(), 1, "Required attached deposit of exactly 1 yoctoNEAR")
-// }
+//used to generate a unique prefix for the set of tokens of a given type
+pub(crate) fn hash_token_type(token_type: &str) -> CryptoHash {
+    let mut hash = CryptoHash::default();
+    hash.copy_from_slice(&env::sha256(token_type.as_bytes()));
+    hash
+}
+
+//the token type (tier) of a credit score: bronze below 500, silver below 700, gold from 700 up
+pub(crate) fn tier_for_score(score: u16) -> &'static str {
+    if score < 500 {
+        "bronze"
+    } else if score < 700 {
+        "silver"
+    } else {
+        "gold"
+    }
+}
+
+//used to make sure the user attached exactly 1 yoctoNEAR, which forces a full access key signature
+pub(crate) fn assert_one_yocto() {
+    assert_eq!(env::attached_deposit(), 1, "Requires attached deposit of exactly 1 yoctoNEAR")
+}
+
+//used to make sure the user attached at least 1 yoctoNEAR, for calls that also pay for storage
+pub(crate) fn assert_at_least_one_yocto() {
+    assert!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR")
+}
+
+//check whether a token's metadata says it has expired, comparing its expiry (in ms) with the block timestamp (in ns)
+pub(crate) fn is_expired(metadata: &TokenMetadata) -> bool {
+    match metadata.expires_at {
+        Some(expires_at) => env::block_timestamp() / 1_000_000 >= expires_at,
+        None => false,
+    }
+}
+
+//parse the credit score embedded in a token's metadata, if any
+pub(crate) fn score_from_metadata(metadata: &TokenMetadata) -> Option<u16> {
+    let extra: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(metadata.extra.as_ref()?).ok()?;
+    let score = extra.get("score")?.as_u64()?;
+    if score > u16::MAX as u64 {
+        return None;
+    }
+    Some(score as u16)
+}
 
-// pub (crate) fn assert_at_least_one_yocto() {
-//     assert!(env::attached_deposit() >= 1, "Requires attached de[osit of at least 1 yoctoNEAR")
-// }
+//refund the cost of the storage released by the contract to a given account
+pub(crate) fn refund_released_storage(account_id: AccountId, storage_released: u64) {
+    let refund = env::storage_byte_cost() * Balance::from(storage_released);
+    //only refund if it's worth a transfer
+    if refund > 1 {
+        Promise::new(account_id).transfer(refund);
+    }
+}
 
 //refund the initial deposit based on the amount of storage that was used up
 pub(crate) fn refund_deposit(storage_used: u64) {
+    refund_deposit_to(storage_used, env::predecessor_account_id())
+}
+
+//refund the initial deposit based on the amount of storage that was used up, sending the surplus to a given account
+pub(crate) fn refund_deposit_to(storage_used: u64, account_id: AccountId) {
     //get how much it would cost to store the information
     let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
     //get the attached deposit
     let attached_deposit = env::attached_deposit();
 
     //make sure that the attached deposit is greater than or equal to the required cost
-    assert!(
-        required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
-        required_cost,
-    );
+    if required_cost > attached_deposit {
+        env::log_str(&format!("Must attach {} yoctoNEAR to cover storage", required_cost));
+        env::panic_str("ERR_DEPOSIT_TOO_LOW");
+    }
 
     //get the refund amount from the attached deposit - required cost
     let refund = attached_deposit - required_cost;
 
-    //if the refund is greater than 1 yocto NEAR, we refund the predecessor that amount
+    //if the refund is greater than 1 yocto NEAR, we refund that amount
     if refund > 1 {
-        Promise::new(env::predecessor_account_id()).transfer(refund);
+        Promise::new(account_id).transfer(refund);
     }
 }
 
-// //convert the royalty percentage and amount to pay into a payout (U128)
-// //we let the minimum royalty percentage be 0.01%, that is 1/10.000
-// pub (crate) fn royalty_to_payout(royalty_percentage: u32, amount_to_pay: Balance) -> U128 {
-//     U128(royalty_percentage as u128 * amount_to_pay / 10_000u128)
-// }
+//log why a mint resolved in a callback was skipped and give the deposit back to the receiver who attached it
+//a panic would refund it to this contract instead, which created the callback receipt
+pub(crate) fn refund_skipped_mint(err: &str, receiver_id: AccountId) {
+    env::log_str(err);
+    if env::attached_deposit() > 0 {
+        Promise::new(receiver_id).transfer(env::attached_deposit());
+    }
+}
+
+//convert the royalty percentage and amount to pay into a payout (U128)
+//we let the minimum royalty percentage be 0.01%, that is 1/10.000
+pub(crate) fn royalty_to_payout(royalty_percentage: u32, amount_to_pay: Balance) -> U128 {
+    U128(royalty_percentage as u128 * amount_to_pay / 10_000u128)
+}
 
 // ------------------------------- //
 //           gas methods           //
@@ -103,6 +166,257 @@ pub(crate) fn refund_deposit(storage_used: u64) {
 // Gas methods == change methods
 
 impl Contract {
+    //panic unless the method is called by the contract owner
+    pub(crate) fn assert_owner(&self) {
+        if env::predecessor_account_id() != self.owner_id {
+            env::panic_str("ERR_ONLY_OWNER");
+        }
+    }
+
+    //return the error explaining why a token can't be transferred at the moment, or None if it can be
+    //tokens carry no transfer count, so unlike soulbound, expiry and pause there is no transfer limit to enforce
+    pub(crate) fn transfer_blocker(&self, token_id: &TokenId, token: &Token) -> Option<&'static str> {
+        //nothing changes hands while the contract is paused
+        if self.paused {
+            return Some("ERR_CONTRACT_PAUSED");
+        }
+        //soulbound tokens stay with the account they were minted to
+        if token.soulbound {
+            return Some("ERR_SOULBOUND_TOKEN");
+        }
+        //expired attestations can't change hands anymore
+        if let Some(metadata) = self.token_metadata_by_id.get(token_id) {
+            if is_expired(&metadata) {
+                return Some("ERR_TOKEN_EXPIRED");
+            }
+        }
+        None
+    }
+
+    //return the error explaining why a token of the given type (tier) can't be minted, or re-minted, at the moment
+    pub(crate) fn mint_type_blocker(&self, token_type: Option<&String>) -> Option<&'static str> {
+        //no token gets minted while the contract is paused
+        if self.paused {
+            return Some("ERR_CONTRACT_PAUSED");
+        }
+        //the issuer may have frozen minting of the token's tier
+        if let Some(token_type) = token_type {
+            if self.locked_token_types.contains(token_type) {
+                return Some("ERR_TOKEN_TYPE_LOCKED");
+            }
+        }
+        None
+    }
+
+    //panic unless a token of the given type (tier) can be minted, or re-minted, at the moment
+    pub(crate) fn assert_mintable(&self, token_type: Option<&String>) {
+        if let Some(err) = self.mint_type_blocker(token_type) {
+            env::panic_str(err);
+        }
+    }
+
+    //return the error explaining why a new token can't be minted to the receiver at the moment, or None if it can be
+    pub(crate) fn mint_blocker(
+        &self,
+        token_id: &TokenId,
+        receiver_id: &AccountId,
+        token_type: Option<&String>,
+    ) -> Option<&'static str> {
+        if let Some(err) = self.mint_type_blocker(token_type) {
+            return Some(err);
+        }
+        //token IDs are unique
+        if self.token_by_id.get(token_id).is_some() {
+            return Some("ERR_TOKEN_EXISTS");
+        }
+        //a deployment may cap how many attestations a single account holds
+        if self.max_tokens_per_owner > 0 {
+            let held = self
+                .tokens_per_owner
+                .get(receiver_id)
+                .map(|tokens| tokens.len())
+                .unwrap_or(0);
+            if held >= self.max_tokens_per_owner as u64 {
+                return Some("ERR_OWNER_TOKEN_LIMIT");
+            }
+        }
+        None
+    }
+
+    //replace the metadata of an existing token, moving it to the tier of the score it now attests to
+    pub(crate) fn internal_update_token_metadata(&mut self, token_id: TokenId, metadata: TokenMetadata) {
+        let mut token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+
+        //a token attesting to a new score moves to the tier of that score
+        if let Some(score) = score_from_metadata(&metadata) {
+            let token_type = tier_for_score(score).to_string();
+            if token.token_type.as_ref() != Some(&token_type) {
+                if let Some(previous_type) = token.token_type.as_ref() {
+                    self.internal_remove_token_from_type(previous_type, &token_id);
+                }
+                self.internal_add_token_to_type(&token_type, &token_id);
+                token.token_type = Some(token_type);
+                self.token_by_id.insert(&token_id, &token);
+            }
+        }
+        self.token_metadata_by_id.insert(&token_id, &metadata);
+
+        //log the metadata update as per the events standard
+        log_event(EventLogVariant::NftMetadataUpdate(vec![NftMetadataUpdateLog {
+            token_ids: vec![token_id],
+            memo: None,
+        }]));
+    }
+
+    //mint a token: index it by ID, store its metadata and add it to the receiver's set of tokens
+    pub(crate) fn internal_mint(
+        &mut self,
+        token_id: TokenId,
+        metadata: TokenMetadata,
+        receiver_id: AccountId,
+        soulbound: Option<bool>,
+        score: Option<u16>,
+        //we add an optional parameter for perpetual royalties
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+    ) {
+        self.internal_mint_unlogged(
+            token_id.clone(),
+            metadata,
+            receiver_id.clone(),
+            soulbound,
+            score,
+            perpetual_royalties,
+        );
+
+        //log the mint as per the events standard
+        log_event(EventLogVariant::NftMint(vec![NftMintLog {
+            //token owner
+            owner_id: receiver_id.to_string(),
+            //vector of token IDs that were minted
+            token_ids: vec![token_id],
+            //an optional memo to include
+            memo: None,
+        }]));
+    }
+
+    //same as internal_mint but without logging the mint, for callers batching the events of several mints
+    pub(crate) fn internal_mint_unlogged(
+        &mut self,
+        token_id: TokenId,
+        mut metadata: TokenMetadata,
+        receiver_id: AccountId,
+        soulbound: Option<bool>,
+        score: Option<u16>,
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+    ) {
+        //the token type (tier) of the score the token attests to, if any
+        let token_type = score.map(|score| tier_for_score(score).to_string());
+        if let Some(err) = self.mint_blocker(&token_id, &receiver_id, token_type.as_ref()) {
+            env::panic_str(err);
+        }
+
+        //create a royalty map to store in the token
+        let mut royalty = HashMap::new();
+
+        //if perpetual royalties were passed into the function:
+        if let Some(perpetual_royalties) = perpetual_royalties {
+            //make sure that the length of the perpetual royalties is below 5
+            //since we won't have enough GAS to pay out that many people
+            if perpetual_royalties.len() >= 5 {
+                env::panic_str("ERR_TOO_MANY_ROYALTIES");
+            }
+            //royalties are in basis points, so together they can't exceed 100% (10000)
+            let total: u64 = perpetual_royalties.values().map(|amount| *amount as u64).sum();
+            if total > 10_000 {
+                env::panic_str("ERR_ROYALTY_TOO_HIGH");
+            }
+
+            //iterate through the perpetual royalties and insert the account and amount in the royalty map
+            for (account, amount) in perpetual_royalties {
+                royalty.insert(account, amount);
+            }
+        }
+
+        //embed the attested score in the metadata as a small JSON blob, e.g. {"score":720}
+        if let Some(score) = score {
+            metadata.extra = Some(near_sdk::serde_json::json!({ "score": score }).to_string());
+        }
+
+        //the account attaching the deposit pays for the token's storage,
+        //which is the receiver when the mint is resolved in a callback of the contract itself
+        let payer_id = if env::predecessor_account_id() == env::current_account_id() {
+            receiver_id.clone()
+        } else {
+            env::predecessor_account_id()
+        };
+
+        //specify the token struct that contains the owner ID
+        let token = Token {
+            //set owner ID to be equal to the receiver ID
+            owner_id: receiver_id,
+            payer_id,
+            //set the soulbound flag
+            soulbound: soulbound.unwrap_or(self.soulbound_by_default),
+            //tokens attesting to a score are grouped by its tier
+            token_type,
+            //set the approved account IDs to the default value (an empty map)
+            approved_account_ids: Default::default(),
+            //the next approval ID is set to 0
+            next_approval_id: 0,
+            //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
+            royalty,
+        };
+
+        //insert the token ID and the token struct,
+        //but first make sure that the token doen't exist -> do this latter part by using
+        //the 'assert!' macro with a custom panic message
+        assert!(
+            self.token_by_id.insert(&token_id, &token).is_none(),
+            "ERR_TOKEN_EXISTS"
+        );
+
+        //insert token id and metadata
+        self.token_metadata_by_id.insert(&token_id, &metadata);
+
+        //call an internal method to add a token to the owner
+        self.internal_add_token_to_owner(&token.owner_id, &token_id);
+
+        //add the token to the set of its type
+        if let Some(token_type) = token.token_type.as_ref() {
+            self.internal_add_token_to_type(token_type, &token_id);
+        }
+
+        //count the mint in the bucket of the current day
+        let day = env::block_timestamp() / NS_PER_DAY;
+        self.mints_per_day.insert(&day, &(self.mints_per_day.get(&day).unwrap_or(0) + 1));
+    }
+
+    //undo a mint that just happened in the same call, for callbacks that must not panic
+    pub(crate) fn internal_unmint(&mut self, token_id: TokenId, memo: Option<String>) {
+        let token = self.internal_burn(&token_id);
+
+        //take the mint back out of the bucket of the current day
+        let day = env::block_timestamp() / NS_PER_DAY;
+        match self.mints_per_day.get(&day).unwrap_or(0) {
+            0 | 1 => {
+                self.mints_per_day.remove(&day);
+            }
+            count => {
+                self.mints_per_day.insert(&day, &(count - 1));
+            }
+        }
+
+        //the mint was already logged, so log the burn too for indexers to stay in sync
+        log_event(EventLogVariant::NftBurn(vec![NftBurnLog {
+            owner_id: token.owner_id.to_string(),
+            token_ids: vec![token_id],
+            memo,
+        }]));
+    }
+
     //add a token to the set of tokens an owner has
     pub(crate) fn internal_add_token_to_owner(
         &mut self,
@@ -129,122 +443,180 @@ impl Contract {
         self.tokens_per_owner.insert(account_id, &tokens_set);
     }
 
-    // pub(crate) fn internal_remove_token_from_owner(
-    //     &mut self, account_id: &AccountId, token_id: &TokenId) {
-    //         // get the set of tokens that the owner has
-    //         let mut tokens_set = self
-    //             .tokens_per_owner
-    //             .get(account_id)
-    //             //if there is no set of tokens for the owner, we panic with the following message:
-    //             .expect("Token should be owned by the sender");
-                
-    //         // remove the the token_id from the set of tokens
-    //         tokens_set.remove(token_id);
-    //         // if the token set is now empty, we remove the owner from the tokens_per_owner collection
-    //         if tokens_set.is_empty() {
-    //             self.tokens_per_owner.remove(account_id);
-    //         } else {
-    //         //if the token set is not empty, we simply insert it back for the account ID.
-    //         self.tokens_per_owner.insert(account_id, &tokens_set);
-    //         }
-    //     }
-
-
-    // //transfers the NFT to the receiver_id (internal method and can't be called directly via CLI).
-    // pub (crate) fn internal_transfer(
-    //     &mut self,
-    //     sender_id: &AccountId,
-    //     receiver_id: &AccountId,
-    //     token_id: &TokenId,
-    //     //we introduce an approval ID so that people with that approval ID can transfer the token
-    //     approval_id: Option<u64>,
-    //     memo: Option<String>,
-    // ) -> Token {
-    //     //get the token object by passing the token_id
-    //     let token = self.token_by_id.get(&token_id).expect("No token");
-
-    //     //if the sender doesn't equal the owner, we panic
-    //     if sender_id != &token.owner_id { 
-    //         //if the token's approved account IDs doesn't contain the sender, we panic
-    //         if !token.approved_account_ids.contains_key(sender_id) {
-    //             env::panic_str("Unauthorized");
-    //         }
-            
-    //         //If they included an approval_id, check if the sender's
-    //         //actual approval_id is the same as the one included
-    //         if let Some(enforced_approval_id) =  approval_id {
-    //             //get the actual approval ID
-    //             let actual_approval_id = token.approved_account_ids.get(sender_id)
-    //             //if the sender isn't in the map we panic 
-    //             .expect("Sender is not approved account");
-
-    //             //make sure that the actual approval ID is the same as the one provided
-    //             assert_eq!(
-    //                 actual_approval_id, &enforced_approval_id,
-    //                 "The actual approval_id {} is different from the given approval_id {}",
-    //                 actual_approval_id, enforced_approval_id,
-    //             );
-    //         }
-    //     }
-
-    //     //make sure that the sender isn't sending the token to themselves
-    //     assert_ne!(&token.owner_id, receiver_id, "The token owner and the receiver should be different");
-
-    //     //remove the token from it's current owner's set
-    //     self.internal_remove_token_from_owner(&token.owner_id, token_id);
-    //     //add the token to the receiver_id's set
-    //     self.internal_add_token_to_owner(receiver_id, token_id);
-
-    //     //create a new token struct 
-    //     let new_token = Token {
-    //         owner_id : receiver_id.clone(),
-    //         //reset the approval account IDs
-    //         approved_account_ids: Default::default(),
-    //         next_approval_id: token.next_approval_id,
-    //         //we copy over the royalties from the previous token
-    //         royalty: token.royalty.clone(),
-    //     };
-
-    //     //insert that new token id into the tokens_by_id, replacing the old entry
-    //     self.token_by_id.insert(token_id, &new_token);
-        
-    //     //if there was some memo attached, then log it
-    //     if let Some(memo) =  memo.as_ref() {
-    //         env::log_str(&format!("Memo: {}", memo).to_string());
-    //     }
-
-    //     //default the authorized ID to be None for the logs
-    //     let mut authorized_id = None;
-    //     //if the approval ID was provided, set the authorized ID equal to the sender
-    //     if approval_id.is_some() {
-    //         authorized_id = Some(sender_id.to_string());
-    //     }
-
-    //     //construct the transfer log as per events standard
-    //     let nft_transfer_log: EventLog = EventLog {
-    //         //standard name ("nep171")
-    //         standard: NFT_STANDARD_NAME.to_string(),
-    //         //version of the standard ("nft-1.0.0")
-    //         version: NFT_METADATA_SPEC.to_string(),
-    //         //the data related with the event stored in a vector
-    //         event: EventLogVariant::NftTransfer(vec![NftTransferLog {
-    //             //the optional authorized account ID to transfer the token on behald of the old owner
-    //             authorized_id,
-    //             //the old owner's account ID
-    //             old_owner_id: token.owner_id.to_string(),
-    //             //the account ID of the new owner of the token
-    //             new_owner_id: receiver_id.to_string(),
-    //             // a vector containing the token IDs as a string
-    //             token_ids: vec![token_id.to_string()],
-    //             //an optional memo to include
-    //             memo,
-    //         }]),
-    //     };
-
-    //     //log the serialized json
-    //     env::log_str(&nft_transfer_log.to_string());
-
-    //     //return the previous token object that was transferred
-    //     token
-    // }
-}
\ No newline at end of file
+    //remove a token from the set of tokens an owner has
+    pub(crate) fn internal_remove_token_from_owner(
+        &mut self, account_id: &AccountId, token_id: &TokenId) {
+            // get the set of tokens that the owner has
+            let mut tokens_set = self
+                .tokens_per_owner
+                .get(account_id)
+                //if there is no set of tokens for the owner, we panic with the following message:
+                .expect("Token should be owned by the sender");
+
+            // remove the the token_id from the set of tokens
+            tokens_set.remove(token_id);
+            // if the token set is now empty, we remove the owner from the tokens_per_owner collection
+            if tokens_set.is_empty() {
+                self.tokens_per_owner.remove(account_id);
+            } else {
+            //if the token set is not empty, we simply insert it back for the account ID.
+            self.tokens_per_owner.insert(account_id, &tokens_set);
+            }
+        }
+
+    //add a token to the set of tokens of a given type
+    pub(crate) fn internal_add_token_to_type(&mut self, token_type: &str, token_id: &TokenId) {
+        let token_type = token_type.to_string();
+        let mut tokens_set = self.tokens_per_type.get(&token_type).unwrap_or_else(|| {
+            //if there are no tokens of that type yet, we create a new unordered set
+            UnorderedSet::new(
+                StorageKey::TokensPerTypeInner {
+                    token_type_hash: hash_token_type(&token_type),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+        tokens_set.insert(token_id);
+        self.tokens_per_type.insert(&token_type, &tokens_set);
+    }
+
+    //remove a token from the set of tokens of a given type
+    pub(crate) fn internal_remove_token_from_type(&mut self, token_type: &str, token_id: &TokenId) {
+        let token_type = token_type.to_string();
+        if let Some(mut tokens_set) = self.tokens_per_type.get(&token_type) {
+            tokens_set.remove(token_id);
+            //drop the set once the last token of that type is gone
+            if tokens_set.is_empty() {
+                self.tokens_per_type.remove(&token_type);
+            } else {
+                self.tokens_per_type.insert(&token_type, &tokens_set);
+            }
+        }
+    }
+
+    //remove a token from all the collections storing it and return the removed token
+    pub(crate) fn internal_burn(&mut self, token_id: &TokenId) -> Token {
+        //remove the token struct, panicking if the token doesn't exist
+        let token = self
+            .token_by_id
+            .remove(token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+        //remove the token metadata
+        self.token_metadata_by_id.remove(token_id);
+        //remove the token from its owner's set
+        self.internal_remove_token_from_owner(&token.owner_id, token_id);
+        //and from the set of its type
+        if let Some(token_type) = token.token_type.as_ref() {
+            self.internal_remove_token_from_type(token_type, token_id);
+        }
+        token
+    }
+
+    //burn a token, log the burn and give the released storage back to whoever paid for it
+    pub(crate) fn internal_burn_and_refund(&mut self, token_id: TokenId, memo: Option<String>) {
+        //measure the storage being used on the contract before the token is removed
+        let initial_storage_usage = env::storage_usage();
+        let token = self.internal_burn(&token_id);
+        let approvals_released: u64 = token.approved_account_ids.keys().map(bytes_for_approved_account_id).sum();
+
+        //log the burn as per the events standard
+        log_event(EventLogVariant::NftBurn(vec![NftBurnLog {
+            //former token owner
+            owner_id: token.owner_id.to_string(),
+            //vector of token IDs that were burned
+            token_ids: vec![token_id],
+            //an optional memo to include
+            memo,
+        }]));
+
+        //the holder paid for the approvals only, the rest of the storage goes back to the account that paid for the token
+        refund_approved_account_ids(token.owner_id, &token.approved_account_ids);
+        refund_released_storage(token.payer_id, initial_storage_usage - env::storage_usage() - approvals_released);
+    }
+
+    //transfers the NFT to the receiver_id (internal method and can't be called directly via CLI).
+    pub(crate) fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> Token {
+        //get the token object by passing the token_id
+        let token = self
+            .token_by_id
+            .get(token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+
+        //make sure that the token can be transferred at all
+        //this is checked first, so that callers get the same answer is_transferable gives them
+        if let Some(err) = self.transfer_blocker(token_id, &token) {
+            env::panic_str(err);
+        }
+
+        //if the sender doesn't equal the owner, they must be an approved account
+        if sender_id != &token.owner_id {
+            //if the token's approved account IDs doesn't contain the sender, we panic
+            let actual_approval_id = token
+                .approved_account_ids
+                .get(sender_id)
+                .unwrap_or_else(|| env::panic_str("ERR_NOT_OWNER"));
+
+            //if they included an approval_id, make sure it is the same as the sender's actual approval_id
+            if let Some(enforced_approval_id) = approval_id {
+                if actual_approval_id != &enforced_approval_id {
+                    env::panic_str("ERR_APPROVAL_ID_MISMATCH");
+                }
+            }
+        }
+
+        //make sure that the sender isn't sending the token to themselves
+        assert_ne!(&token.owner_id, receiver_id, "The token owner and the receiver should be different");
+
+        //remove the token from it's current owner's set
+        self.internal_remove_token_from_owner(&token.owner_id, token_id);
+        //add the token to the receiver_id's set
+        self.internal_add_token_to_owner(receiver_id, token_id);
+
+        //create a new token struct
+        let new_token = Token {
+            owner_id: receiver_id.clone(),
+            payer_id: token.payer_id.clone(),
+            soulbound: token.soulbound,
+            token_type: token.token_type.clone(),
+            //reset the approval account IDs
+            approved_account_ids: Default::default(),
+            next_approval_id: token.next_approval_id,
+            //we copy over the royalties from the previous token
+            royalty: token.royalty.clone(),
+        };
+
+        //insert that new token id into the tokens_by_id, replacing the old entry
+        self.token_by_id.insert(token_id, &new_token);
+
+        //an approved account transferring the token is logged as the authorized ID
+        let authorized_id = if sender_id != &token.owner_id {
+            Some(sender_id.to_string())
+        } else {
+            None
+        };
+
+        //log the transfer as per the events standard
+        log_event(EventLogVariant::NftTransfer(vec![NftTransferLog {
+            authorized_id,
+            //previous token owner
+            old_owner_id: token.owner_id.to_string(),
+            //new token owner
+            new_owner_id: receiver_id.to_string(),
+            //vector of token IDs that were transferred
+            token_ids: vec![token_id.to_string()],
+            //an optional memo to include
+            memo,
+        }]));
+
+        //return the previous token object that was transferred
+        token
+    }
+}
@@ -7,18 +7,35 @@ use near_sdk::{
 };
 use std::collections::HashMap;
 
+pub use crate::approval::*;
 pub use crate::enumerate::*;
+pub use crate::events::*;
 pub use crate::internal::*;
 pub use crate::metadata::*;
 pub use crate::mint::*;
 pub use crate::nft_core::*;
+pub use crate::royalty::*;
 
+mod approval;
 mod enumerate;
+mod events;
 mod internal;
 mod metadata;
 mod mint;
 mod nft_core;
+mod royalty;
 
+#[cfg(test)]
+mod tests;
+
+//name of the NFT standard and version of its events, used in the event logs
+pub const NFT_STANDARD_NAME: &str = "nep171";
+pub const NFT_EVENTS_VERSION: &str = "1.0.0";
+//nft_metadata_update only came with version 1.1.0 of the NFT standard
+pub const NFT_METADATA_UPDATE_VERSION: &str = "1.1.0";
+//name and version of the contract's own events, which the NFT standard doesn't define
+pub const CONTRACT_STANDARD_NAME: &str = "credit_score_nft";
+pub const CONTRACT_EVENTS_VERSION: &str = "1.0.0";
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -37,6 +54,33 @@ pub struct Contract {
 
     //metadata for the contract
     pub metadata: LazyOption<NFTContractMetadata>,
+
+    //whether tokens minted without an explicit soulbound flag are soulbound
+    pub soulbound_by_default: bool,
+
+    //accounts allowed to mint on top of the contract owner
+    pub authorized_minters: UnorderedSet<AccountId>,
+
+    //token IDs for a given token type (score tier)
+    pub tokens_per_type: LookupMap<String, UnorderedSet<TokenId>>,
+
+    //token types (score tiers) that can't be minted for the time being
+    pub locked_token_types: UnorderedSet<String>,
+
+    //whether minting and transfers are suspended
+    pub paused: bool,
+
+    //maximum number of tokens an account can be minted, 0 meaning unlimited
+    pub max_tokens_per_owner: u32,
+
+    //number of tokens minted on a given day, counted in days since the unix epoch
+    pub mints_per_day: LookupMap<u64, u64>,
+
+    //credit-score oracle mint_if_qualified trusts, None until the owner sets one
+    pub score_oracle: Option<AccountId>,
+
+    //lowest score the oracle must return for mint_if_qualified to mint a token
+    pub min_mint_score: u16,
 }
 /*
 Notice: the 'Contract' struct comprises of some custom data types, which we'll summarize here below:
@@ -56,6 +100,8 @@ pub enum StorageKey {
     TokensPerType,
     TokensPerTypeInner { token_type_hash: CryptoHash },
     TokenTypesLocked,
+    AuthorizedMinters,
+    MintsPerDay,
 }
 
 #[near_bindgen]
@@ -79,6 +125,8 @@ impl Contract {
                 reference: None,
                 reference_hash: None,
             },
+            None,
+            None,
         )
     }
 
@@ -86,9 +134,23 @@ impl Contract {
         initialization function (can only be called once).
         this initializes the contract with the metadata and
         the owner_id that got fed to the function.
+        credit-score NFTs are soulbound unless soulbound_by_default is set to false.
+        max_tokens_per_owner caps how many tokens a single account can be minted, 0 (the default) meaning unlimited.
     */
     #[init]
-    pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
+    pub fn new(
+        owner_id: AccountId,
+        metadata: NFTContractMetadata,
+        soulbound_by_default: Option<bool>,
+        max_tokens_per_owner: Option<u32>,
+    ) -> Self {
+        //wallets render the icon as a data URI, so anything else is a misconfiguration
+        if let Some(icon) = metadata.icon.as_ref() {
+            if !icon.starts_with("data:image/") {
+                env::panic_str("ERR_INVALID_ICON");
+            }
+        }
+
         //create a variable of type Self initializing all fields
         let this = Self {
             //set the owner_id field equal to the passed in owner_id
@@ -105,6 +167,24 @@ impl Contract {
             token_metadata_by_id: UnorderedMap::new(
                 StorageKey::TokenMetadataById.try_to_vec().unwrap(),
             ),
+
+            soulbound_by_default: soulbound_by_default.unwrap_or(true),
+
+            authorized_minters: UnorderedSet::new(StorageKey::AuthorizedMinters.try_to_vec().unwrap()),
+
+            tokens_per_type: LookupMap::new(StorageKey::TokensPerType.try_to_vec().unwrap()),
+
+            locked_token_types: UnorderedSet::new(StorageKey::TokenTypesLocked.try_to_vec().unwrap()),
+
+            paused: false,
+
+            max_tokens_per_owner: max_tokens_per_owner.unwrap_or(0),
+
+            mints_per_day: LookupMap::new(StorageKey::MintsPerDay.try_to_vec().unwrap()),
+
+            score_oracle: None,
+
+            min_mint_score: 0,
         };
 
         //return the Contract object
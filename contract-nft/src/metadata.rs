@@ -40,12 +40,18 @@ pub struct TokenMetadata {
 pub struct Token {
     //define token owner
     pub owner_id: AccountId,
-    // //list of approved account IDs that have access to transfer the token. This maps an account ID to an approval ID
-    // pub approved_account_ids: HashMap<AccountId, u64>,
-    // //the next approval ID
-    // pub next_approval_id: u64,
-    // //perfentage of royalty to be paid to an account
-    // pub royalty: HashMap<AccountId, u32>,
+    //account that paid for the token's storage, refunded once the token is burned
+    pub payer_id: AccountId,
+    //soulbound tokens stay bound to the account they were minted to
+    pub soulbound: bool,
+    //score tier of the token ("bronze", "silver" or "gold"), for tokens attesting to a score
+    pub token_type: Option<String>,
+    //list of approved account IDs that have access to transfer the token. This maps an account ID to an approval ID
+    pub approved_account_ids: HashMap<AccountId, u64>,
+    //the next approval ID
+    pub next_approval_id: u64,
+    //percentage of royalty to be paid to an account, in basis points
+    pub royalty: HashMap<AccountId, u32>,
 }
 
 //The Json token is what will be returned from view calls. This object exists off-chain only. It holds all the information
@@ -59,10 +65,10 @@ pub struct JsonToken {
     pub owner_id: AccountId,
     //token metadata
     pub metadata: TokenMetadata,
-    // // list of approved account IDs that have access to transfer the token. This maps an account ID to an approval ID
-    // pub approved_account_ids: HashMap<AccountId, u64>,
-    // //perfentage of royalty to be paid to an account
-    // pub royalty: HashMap<AccountId, u32>,
+    //list of approved account IDs that have access to transfer the token. This maps an account ID to an approval ID
+    pub approved_account_ids: HashMap<AccountId, u64>,
+    //percentage of royalty to be paid to an account, in basis points
+    pub royalty: HashMap<AccountId, u32>,
 }
 
 /*
@@ -81,4 +87,27 @@ impl NonFungibleTokenMetadata for Contract {
     fn nft_metadata(&self) -> NFTContractMetadata {
         self.metadata.get().unwrap()
     }
-}
\ No newline at end of file
+}
+
+#[near_bindgen]
+impl Contract {
+    //view call returning the credit score attested by a token, if it has one
+    pub fn token_score(&self, token_id: TokenId) -> Option<u16> {
+        self.token_metadata_by_id
+            .get(&token_id)
+            .and_then(|metadata| score_from_metadata(&metadata))
+    }
+
+    //view call returning the token type (score tier) of a token, if it has one
+    pub fn token_type(&self, token_id: TokenId) -> Option<String> {
+        self.token_by_id
+            .get(&token_id)
+            .and_then(|token| token.token_type)
+    }
+
+    //owner-only: replace the metadata of a token, e.g. once the credit score it attests to changed tier
+    pub fn update_token_metadata(&mut self, token_id: TokenId, metadata: TokenMetadata) {
+        self.assert_owner();
+        self.internal_update_token_metadata(token_id, metadata);
+    }
+}
@@ -1,4 +1,30 @@
 use crate::*;
+use near_sdk::{ext_contract, Gas, PromiseResult};
+use std::collections::{BTreeMap, HashSet};
+
+const GAS_FOR_SCORE_QUERY: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_RESOLVE_MINT: Gas = Gas(30_000_000_000_000);
+const GAS_FOR_RESOLVE_BURN: Gas = Gas(20_000_000_000_000);
+//maximum number of tokens minted by a single nft_batch_mint call
+const MAX_BATCH_MINT: usize = 50;
+
+//the credit-score oracle contract exposes the latest score of each account
+#[ext_contract(ext_oracle)]
+trait ScoreOracle {
+    fn query_latest_score(&self, account_id: String) -> u16;
+}
+
+//callback minting the token once the oracle returned the caller's latest score
+#[ext_contract(ext_mint_resolver)]
+trait MintResolver {
+    fn resolve_mint_if_qualified(&mut self, token_id: TokenId, receiver_id: AccountId, threshold: u16) -> bool;
+}
+
+//callback burning the token once the oracle returned its owner's latest score
+#[ext_contract(ext_burn_resolver)]
+trait BurnResolver {
+    fn resolve_burn_if_below(&mut self, token_id: TokenId, threshold: u16) -> bool;
+}
 
 #[near_bindgen]
 impl Contract {
@@ -8,77 +34,414 @@ impl Contract {
         token_id: TokenId,
         metadata: TokenMetadata,
         receiver_id: AccountId,
-        // //we add an optional parameter for perpetual royalties
-        // perpetual_royalties: Option<HashMap<AccountId, u32>>,
-) {
+        //flag the token as soulbound (non-transferable), defaults to the contract-wide setting
+        soulbound: Option<bool>,
+        //the credit score this token attests to, embedded in the metadata's extra field
+        score: Option<u16>,
+        //perpetual royalties (in basis points) paid out on every sale of the token
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        //re-minting a token the receiver already holds updates its metadata instead of panicking
+        upsert: Option<bool>,
+    ) {
+        //credit-score attestations are issued by the contract owner and the minters it authorized
+        let minter = env::predecessor_account_id();
+        if minter != self.owner_id && !self.authorized_minters.contains(&minter) {
+            env::panic_str("ERR_ONLY_OWNER_CAN_MINT");
+        }
+
         //measure the initial storage being used on the contract
         let initial_storage_usage = env::storage_usage();
 
-        // //create a royalty map to store in the token
-        // let mut royalty = HashMap::new();
-
-        // //if perpetual royalties were passed into the function:
-        // if let Some(perpetual_royalties) = perpetual_royalties {
-        //     //make sure that the length of the perpetual royalties is below 5
-        //     //since we won't have enough GAS to pay out that many people
-        //     assert!(perpetual_royalties.len() < 5, "Cannot add more than 4 perpetual royalty amounts");
-
-        //     //iterate through the perpetual royalties and insert the account and amount in the royalty map
-        //     for (account, amount) in perpetual_royalties {
-        //         royalty.insert(account, amount);
-        //     }
-        // }
-
-        //specify the token struct that contains the owner ID
-        let token = Token {
-            //set owner ID to be equal to the receiver ID
-            owner_id: receiver_id,
-            // //set the approved account IDs to the default value (an empty map)
-            // approved_account_ids: Default::default(),
-            // //the next approval ID is set to 0
-            // next_approval_id: 0,
-            // //the map of perpetual royalties for the token (The owner will get 100% - total perpetual royalties)
-            // royalty,
-        };
-
-        //insert the token ID and the token struct,
-        //but first make sure that the token doen't exist -> do this latter part by using
-        //the 'assert!' macro with a custom panic message
-        assert!(
-            self.token_by_id.insert(&token_id, &token).is_none(),
-            "Token already exists"
-        );
-
-        //insert token id and metadata
-        self.token_metadata_by_id.insert(&token_id, &metadata);
-
-        //call an internal method to add a token to the owner
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
-
-        // //construct the mint log as per the events standard
-        // let nft_mint_log: EventLog = EventLog {
-        //     //standard name ("nep171")
-        //     standard: NFT_STANDARD_NAME.to_string(),
-        //     //version of the standard ("nft-1.0.0")
-        //     version: NFT_METADATA_SPEC.to_string(),
-        //     //the data related with the event stored in a vector
-        //     event: EventLogVariant::NftMint(vec![NftMintLog {
-        //         //token owner
-        //         owner_id: token.owner_id.to_string(),
-        //         //vector of token IDs that were minted
-        //         token_ids: vec![token_id.to_string()],
-        //         //an optional memo to include
-        //         memo: None,
-        //     }]),
-        // };
-
-        // //log the serialized json
-        // env::log_str(&nft_mint_log.to_string());
+        match self.token_by_id.get(&token_id) {
+            //the same receiver re-minting, e.g. after a metadata correction, gets its token updated
+            Some(token) if upsert.unwrap_or(false) && token.owner_id == receiver_id => {
+                //re-minting is subject to the same pause and tier locks as minting
+                self.assert_mintable(score.map(|score| tier_for_score(score).to_string()).as_ref());
+                let mut metadata = metadata;
+                if let Some(score) = score {
+                    metadata.extra = Some(near_sdk::serde_json::json!({ "score": score }).to_string());
+                }
+                self.internal_update_token_metadata(token_id, metadata);
+            }
+            //mint the token, internal_mint rejects IDs that are already taken
+            _ => self.internal_mint(token_id, metadata, receiver_id, soulbound, score, perpetual_royalties),
+        }
 
         //calculate the required storage = used storage - initial storage
-        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+        //(an update may even shrink the metadata, in which case there's nothing to pay for)
+        let required_storage_in_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
 
         //refund surplus storage to user OR panic if they didn't attach enough to cover for the required gas fee
         refund_deposit(required_storage_in_bytes);
     }
-}
\ No newline at end of file
+
+    //mint a token under the next free deterministic ID, see next_token_id, and return that ID
+    #[payable]
+    pub fn nft_mint_auto(
+        &mut self,
+        metadata: TokenMetadata,
+        receiver_id: AccountId,
+        soulbound: Option<bool>,
+        score: Option<u16>,
+        perpetual_royalties: Option<HashMap<AccountId, u32>>,
+    ) -> TokenId {
+        let token_id = self.next_token_id();
+        self.nft_mint(token_id.clone(), metadata, receiver_id, soulbound, score, perpetual_royalties, None);
+        token_id
+    }
+
+    //the ID nft_mint_auto mints its next token under, e.g. "score-3" once 3 tokens exist
+    //IDs already taken, e.g. by tokens minted with an explicit ID, are skipped
+    pub fn next_token_id(&self) -> TokenId {
+        let mut index = self.token_metadata_by_id.len();
+        loop {
+            let token_id = format!("score-{}", index);
+            if self.token_by_id.get(&token_id).is_none() {
+                return token_id;
+            }
+            index += 1;
+        }
+    }
+
+    //owner-only: mint many tokens at once, e.g. when onboarding many users
+    //the whole batch is rejected if any token ID is already taken
+    #[payable]
+    pub fn nft_batch_mint(&mut self, mints: Vec<(TokenId, TokenMetadata, AccountId)>) {
+        self.assert_owner();
+        if mints.len() > MAX_BATCH_MINT {
+            env::panic_str("ERR_BATCH_TOO_LARGE");
+        }
+        //check every token ID before mutating any state, including duplicates within the batch
+        let mut token_ids = HashSet::new();
+        for (token_id, _, _) in mints.iter() {
+            if self.token_by_id.get(token_id).is_some() || !token_ids.insert(token_id) {
+                env::panic_str("ERR_TOKEN_EXISTS");
+            }
+        }
+
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        //keep track of the tokens minted to each receiver, BTreeMaps keep the batched event deterministic
+        let mut minted: BTreeMap<AccountId, Vec<String>> = BTreeMap::new();
+        for (token_id, metadata, receiver_id) in mints {
+            self.internal_mint_unlogged(token_id.clone(), metadata, receiver_id.clone(), None, None, None);
+            minted.entry(receiver_id).or_insert_with(Vec::new).push(token_id);
+        }
+
+        //log a single, batched mint event with one entry per receiver
+        if !minted.is_empty() {
+            let mint_logs = minted
+                .into_iter()
+                .map(|(owner_id, token_ids)| NftMintLog {
+                    owner_id: owner_id.to_string(),
+                    token_ids,
+                    memo: None,
+                })
+                .collect();
+            log_event(EventLogVariant::NftMint(mint_logs));
+        }
+
+        //a single deposit covers the storage of the whole batch
+        refund_deposit(env::storage_usage() - initial_storage_usage);
+    }
+
+    //burn a token, e.g. once the loan its credit score attested to got serviced
+    //only the token owner or the contract owner can burn a token
+    pub fn nft_burn(&mut self, token_id: TokenId) {
+        let token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+        let caller = env::predecessor_account_id();
+        if caller != token.owner_id && caller != self.owner_id {
+            env::panic_str("ERR_NOT_AUTHORIZED");
+        }
+
+        self.internal_burn_and_refund(token_id, None);
+    }
+
+    //owner-only housekeeping: burn the expired tokens found in a page of the collection
+    //returns the number of tokens burned
+    pub fn burn_expired(&mut self, from_index: Option<U128>, limit: Option<u64>) -> u64 {
+        self.assert_owner();
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        //collect the expired token IDs first, since we can't remove them while iterating
+        let expired: Vec<TokenId> = self
+            .token_metadata_by_id
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .filter(|(_, metadata)| is_expired(metadata))
+            .map(|(token_id, _)| token_id)
+            .collect();
+
+        //burn the tokens, keeping track of the tokens burned for each owner
+        //and of the storage released for each account that paid for it
+        //BTreeMaps keep the batched event and the refunds deterministic
+        let mut burned: BTreeMap<AccountId, Vec<String>> = BTreeMap::new();
+        let mut released: BTreeMap<AccountId, u64> = BTreeMap::new();
+        for token_id in expired.iter() {
+            let initial_storage_usage = env::storage_usage();
+            let token = self.internal_burn(token_id);
+            //the holder paid for the approvals only, the payer for the rest of the token
+            let approvals_released: u64 = token.approved_account_ids.keys().map(bytes_for_approved_account_id).sum();
+            *released.entry(token.owner_id.clone()).or_insert(0) += approvals_released;
+            *released.entry(token.payer_id).or_insert(0) += initial_storage_usage - env::storage_usage() - approvals_released;
+            burned.entry(token.owner_id).or_insert_with(Vec::new).push(token_id.clone());
+        }
+
+        if !burned.is_empty() {
+            let burn_logs = burned
+                .into_iter()
+                .map(|(owner_id, token_ids)| NftBurnLog {
+                    owner_id: owner_id.to_string(),
+                    token_ids,
+                    memo: Some("expired".to_string()),
+                })
+                .collect();
+            //log a single, batched burn event
+            log_event(EventLogVariant::NftBurn(burn_logs));
+
+            //give the released storage back to each account that paid for it in a single transfer
+            for (payer_id, storage_released) in released.into_iter() {
+                refund_released_storage(payer_id, storage_released);
+            }
+        }
+
+        expired.len() as u64
+    }
+
+    //mint a credit-score NFT to the caller iff the trusted oracle says their latest score clears min_mint_score
+    //both are set by the owner, see set_score_oracle, so callers can't vouch for their own score
+    //the attached deposit is forwarded to the callback to cover the token's storage
+    #[payable]
+    pub fn mint_if_qualified(&mut self, token_id: TokenId) -> Promise {
+        let oracle = self
+            .score_oracle
+            .clone()
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SCORE_ORACLE"));
+        let threshold = self.min_mint_score;
+        let receiver_id = env::predecessor_account_id();
+        //fail before the query for what can be checked now, the tier is only known once the score is
+        if let Some(err) = self.mint_blocker(&token_id, &receiver_id, None) {
+            env::panic_str(err);
+        }
+        ext_oracle::ext(oracle)
+            .with_static_gas(GAS_FOR_SCORE_QUERY)
+            .query_latest_score(receiver_id.to_string())
+        .then(
+            ext_mint_resolver::ext(env::current_account_id())
+                .with_attached_deposit(env::attached_deposit())
+                .with_static_gas(GAS_FOR_RESOLVE_MINT)
+                .resolve_mint_if_qualified(token_id, receiver_id, threshold),
+        )
+    }
+
+    //callback of mint_if_qualified: mint only when the returned score is at least the threshold
+    //returns whether the token got minted
+    #[private]
+    #[payable]
+    pub fn resolve_mint_if_qualified(&mut self, token_id: TokenId, receiver_id: AccountId, threshold: u16) -> bool {
+        let qualified = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<u16>(&value).map_err(|_| "ERR_INVALID_SCORE")
+            }
+            _ => Err("ERR_SCORE_QUERY_FAILED"),
+        }
+        .and_then(|score| if score < threshold { Err("ERR_SCORE_BELOW_THRESHOLD") } else { Ok(score) });
+
+        //the token may have been minted, or the contract paused or the tier locked, since mint_if_qualified was called
+        let qualified = qualified.and_then(|score| {
+            match self.mint_blocker(&token_id, &receiver_id, Some(&tier_for_score(score).to_string())) {
+                Some(err) => Err(err),
+                None => Ok(score),
+            }
+        });
+
+        //this callback doesn't panic, see refund_skipped_mint
+        let score = match qualified {
+            Ok(score) => score,
+            Err(err) => {
+                refund_skipped_mint(err, receiver_id);
+                return false;
+            }
+        };
+
+        let metadata = TokenMetadata {
+            title: "Credit score NFT".to_string(),
+            description: format!("Attests a credit score of {}", score),
+            media: "".to_string(),
+            media_hash: None,
+            copies: Some(1),
+            //unix epoch in milliseconds
+            issued_at: env::block_timestamp() / 1_000_000,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference: None,
+            reference_hash: None,
+        };
+
+        //measure the storage used by the token, the surplus deposit goes back to the receiver
+        let initial_storage_usage = env::storage_usage();
+        self.internal_mint(token_id.clone(), metadata, receiver_id.clone(), None, Some(score), None);
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        //a deposit too low for the token is only known once it's minted, so the mint is rolled back
+        if env::storage_byte_cost() * Balance::from(storage_used) > env::attached_deposit() {
+            self.internal_unmint(token_id, Some("deposit too low".to_string()));
+            refund_skipped_mint("ERR_DEPOSIT_TOO_LOW", receiver_id);
+            return false;
+        }
+        refund_deposit_to(storage_used, receiver_id);
+        true
+    }
+
+    //owner-only: set the oracle mint_if_qualified queries and the lowest score it mints a token for
+    pub fn set_score_oracle(&mut self, oracle: AccountId, min_score: u16) {
+        self.assert_owner();
+        self.score_oracle = Some(oracle.clone());
+        self.min_mint_score = min_score;
+        log_event(EventLogVariant::ScoreOracleUpdate(vec![ScoreOracleUpdateLog {
+            oracle: oracle.to_string(),
+            min_score,
+        }]));
+    }
+
+    //the oracle mint_if_qualified queries, if the owner set one
+    pub fn get_score_oracle(&self) -> Option<AccountId> {
+        self.score_oracle.clone()
+    }
+
+    //the lowest score mint_if_qualified mints a token for
+    pub fn get_min_mint_score(&self) -> u16 {
+        self.min_mint_score
+    }
+
+    //owner-only: revoke a credit-score NFT iff the oracle says its owner's latest score dropped below a threshold
+    pub fn burn_if_below(&mut self, token_id: TokenId, oracle: AccountId, threshold: u16) -> Promise {
+        self.assert_owner();
+        let token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+        ext_oracle::ext(oracle)
+            .with_static_gas(GAS_FOR_SCORE_QUERY)
+            .query_latest_score(token.owner_id.to_string())
+        .then(
+            ext_burn_resolver::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_BURN)
+                .resolve_burn_if_below(token_id, threshold),
+        )
+    }
+
+    //callback of burn_if_below: burn only when the returned score is below the threshold
+    //returns whether the token got burned
+    #[private]
+    pub fn resolve_burn_if_below(&mut self, token_id: TokenId, threshold: u16) -> bool {
+        let score = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<u16>(&value)
+                .unwrap_or_else(|_| env::panic_str("ERR_INVALID_SCORE")),
+            _ => env::panic_str("ERR_SCORE_QUERY_FAILED"),
+        };
+        //the token may have been burned in the meantime
+        if score >= threshold || self.token_by_id.get(&token_id).is_none() {
+            return false;
+        }
+        self.internal_burn_and_refund(token_id, Some("score below threshold".to_string()));
+        true
+    }
+
+    //hand the contract over to another owner, who from then on mints and administers it
+    #[payable]
+    pub fn transfer_contract_ownership(&mut self, new_owner: AccountId) {
+        //assert that the user attached exactly 1 yoctoNEAR for security reasons
+        assert_one_yocto();
+        self.assert_owner();
+        if new_owner == self.owner_id {
+            env::panic_str("ERR_SAME_OWNER");
+        }
+        log_event(EventLogVariant::ContractOwnerTransfer(vec![ContractOwnerTransferLog {
+            old_owner_id: self.owner_id.to_string(),
+            new_owner_id: new_owner.to_string(),
+        }]));
+        self.owner_id = new_owner;
+    }
+
+    //allow an account to mint tokens
+    pub fn add_minter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.authorized_minters.insert(&account_id);
+        log_event(EventLogVariant::MinterUpdate(vec![MinterUpdateLog {
+            account_id: account_id.to_string(),
+            authorized: true,
+        }]));
+    }
+
+    //revoke an account's permission to mint tokens
+    pub fn remove_minter(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        self.authorized_minters.remove(&account_id);
+        log_event(EventLogVariant::MinterUpdate(vec![MinterUpdateLog {
+            account_id: account_id.to_string(),
+            authorized: false,
+        }]));
+    }
+
+    //list the accounts allowed to mint tokens on top of the contract owner
+    pub fn get_minters(&self) -> Vec<AccountId> {
+        self.authorized_minters.to_vec()
+    }
+
+    //freeze minting of a token type (score tier) without pausing the whole contract
+    pub fn lock_token_type(&mut self, token_type: String) {
+        self.assert_owner();
+        self.locked_token_types.insert(&token_type);
+        log_event(EventLogVariant::TokenTypeLockUpdate(vec![TokenTypeLockUpdateLog {
+            token_type,
+            locked: true,
+        }]));
+    }
+
+    //allow minting of a token type (score tier) again
+    pub fn unlock_token_type(&mut self, token_type: String) {
+        self.assert_owner();
+        self.locked_token_types.remove(&token_type);
+        log_event(EventLogVariant::TokenTypeLockUpdate(vec![TokenTypeLockUpdateLog {
+            token_type,
+            locked: false,
+        }]));
+    }
+
+    //list the token types that can't be minted for the time being
+    pub fn get_locked_token_types(&self) -> Vec<String> {
+        self.locked_token_types.to_vec()
+    }
+
+    //suspend minting and transfers, view methods remain available
+    pub fn pause(&mut self) {
+        self.assert_owner();
+        self.paused = true;
+        log_event(EventLogVariant::ContractPauseUpdate(vec![ContractPauseUpdateLog { paused: true }]));
+    }
+
+    //resume minting and transfers
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        self.paused = false;
+        log_event(EventLogVariant::ContractPauseUpdate(vec![ContractPauseUpdateLog { paused: false }]));
+    }
+
+    //whether minting and transfers are suspended
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    //number of tokens minted on a given day, counted in days since the unix epoch
+    pub fn mints_on_day(&self, day: u64) -> u64 {
+        self.mints_per_day.get(&day).unwrap_or(0)
+    }
+}
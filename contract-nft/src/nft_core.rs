@@ -1,9 +1,60 @@
 use crate::*;
-use near_sdk::{ext_contract, log, Gas, PromiseResult};
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_NFT_ON_TRANSFER: Gas = Gas(25_000_000_000_000);
 
 pub trait NonFungibleTokenCore {
     //get information about the NFT token passed in
     fn json_token(&self, token_id: TokenId) -> Option<JsonToken>;
+
+    //standard (NEP-171) view call returning the information about a given token
+    fn nft_token(&self, token_id: TokenId) -> Option<JsonToken>;
+
+    //transfer an NFT to a receiver ID
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+
+    //transfer an NFT to a receiver and call a function on the receiver ID's contract
+    /// Returns `true` if the token was transferred from the sender's account.
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
+
+//the receiver contract must implement nft_on_transfer, returning true if the token should be given back to the sender
+#[ext_contract(ext_non_fungible_token_receiver)]
+trait NonFungibleTokenReceiver {
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> Promise;
+}
+
+//the callback resolving nft_transfer_call, which reverts the transfer if the receiver asked to
+#[ext_contract(ext_self)]
+pub trait NonFungibleTokenResolver {
+    fn nft_resolve_transfer(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        //the approvals the token had before the transfer, restored if the transfer is reverted
+        approved_account_ids: HashMap<AccountId, u64>,
+    ) -> bool;
 }
 
 #[near_bindgen]
@@ -20,8 +71,8 @@ impl NonFungibleTokenCore for Contract {
                 token_id,
                 owner_id: token.owner_id,
                 metadata,
-                // approved_account_ids: token.approved_account_ids,
-                // royalty: token.royalty,
+                approved_account_ids: token.approved_account_ids,
+                royalty: token.royalty,
             })
 
         //if there is no token ID in the token_by_id_collection, then return None
@@ -29,4 +80,150 @@ impl NonFungibleTokenCore for Contract {
             None
         }
     }
-}
\ No newline at end of file
+
+    //get the information for a specific token ID, this is the view call wallets use to display an NFT
+    fn nft_token(&self, token_id: TokenId) -> Option<JsonToken> {
+        self.json_token(token_id)
+    }
+
+    //implementation of the nft_transfer method. This transfers the NFT from the current owner to the receiver.
+    #[payable]
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        //assert that the user attached exactly 1 yoctoNEAR. This is for security and so that the user will be redirected to the NEAR wallet.
+        assert_one_yocto();
+        //get the sender to transfer the token from the sender to the receiver
+        let sender_id = env::predecessor_account_id();
+
+        //call the internal transfer method and get the previous token object
+        let previous_token = self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+
+        //the transfer cleared the approvals, so refund their storage to the previous owner who paid for it
+        refund_approved_account_ids(previous_token.owner_id, &previous_token.approved_account_ids);
+    }
+
+    //implementation of the transfer call method. This will transfer the NFT and call a method on the receiver_id contract
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        //assert that the user attached exactly 1 yocto for security reasons
+        assert_one_yocto();
+        //get the sender ID
+        let sender_id = env::predecessor_account_id();
+
+        //transfer the token and get the previous token object
+        let previous_token = self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
+
+        //initiate the receiver's call and the callback
+        ext_non_fungible_token_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_NFT_ON_TRANSFER)
+            .nft_on_transfer(sender_id, previous_token.owner_id.clone(), token_id.clone(), msg)
+        //we then resolve the promise and call nft_resolve_transfer on our own contract
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                .nft_resolve_transfer(
+                    previous_token.owner_id,
+                    receiver_id,
+                    token_id,
+                    previous_token.approved_account_ids,
+                ),
+        )
+        .into()
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenResolver for Contract {
+    //resolves the cross contract call when calling nft_on_transfer in the nft_transfer_call method
+    //returns true if the token was successfully transferred to the receiver_id
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: HashMap<AccountId, u64>,
+    ) -> bool {
+        //whether the receiver wants to return the token back to the sender, based on the promise result
+        if let PromiseResult::Successful(value) = env::promise_result(0) {
+            //as per the standard, the nft_on_transfer should return whether we should return the token
+            if let Ok(return_token) = near_sdk::serde_json::from_slice::<bool>(&value) {
+                //if we need don't need to return the token, we refund the previous owner's approvals
+                //and return true meaning everything went fine
+                if !return_token {
+                    refund_approved_account_ids(owner_id, &approved_account_ids);
+                    return true;
+                }
+            }
+        }
+
+        //get the token object if there is some token object
+        let mut token = if let Some(token) = self.token_by_id.get(&token_id) {
+            //if the token's owner changed in the meantime, there is nothing to revert
+            if token.owner_id != receiver_id {
+                refund_approved_account_ids(owner_id, &approved_account_ids);
+                return true;
+            }
+            token
+        //if there isn't a token object, it was burned and so we return true
+        } else {
+            refund_approved_account_ids(owner_id, &approved_account_ids);
+            return true;
+        };
+
+        //if we haven't returned true, we should return the token to its original owner
+        //we remove the token from the receiver
+        self.internal_remove_token_from_owner(&receiver_id, &token_id);
+        //we add the token to the original owner
+        self.internal_add_token_to_owner(&owner_id, &token_id);
+
+        //the receiver's approvals are dropped, so refund their storage to the receiver
+        refund_approved_account_ids(receiver_id.clone(), &token.approved_account_ids);
+
+        //we change the token struct's owner to be the original owner and restore its approvals
+        token.owner_id = owner_id.clone();
+        token.approved_account_ids = approved_account_ids;
+        self.token_by_id.insert(&token_id, &token);
+
+        //log the token going back as a transfer, as per the events standard
+        log_event(EventLogVariant::NftTransfer(vec![NftTransferLog {
+            authorized_id: None,
+            old_owner_id: receiver_id.to_string(),
+            new_owner_id: owner_id.to_string(),
+            token_ids: vec![token_id],
+            memo: Some("returned by receiver".to_string()),
+        }]));
+
+        //return false, since the token was not transferred
+        false
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    //whether nft_transfer would currently succeed for the token's owner (ignoring the 1 yoctoNEAR deposit)
+    pub fn is_transferable(&self, token_id: TokenId) -> bool {
+        match self.token_by_id.get(&token_id) {
+            Some(token) => self.transfer_blocker(&token_id, &token).is_none(),
+            //unknown tokens can't be transferred
+            None => false,
+        }
+    }
+
+    //the account owning a token, without pulling the whole token
+    pub fn whose_token(&self, token_id: TokenId) -> Option<AccountId> {
+        self.token_by_id.get(&token_id).map(|token| token.owner_id)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::*;
+
+pub trait NonFungibleTokenRoyalty {
+    //calculates the payout for a token given the passed in balance. This is a view method
+    fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout;
+
+    //transfers the token to the receiver ID and returns the payout object that should be paid given the passed in balance
+    fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: u64,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+    ) -> Payout;
+}
+
+//split a sale balance between the royalty accounts and the token owner, who gets whatever is left
+fn compute_payout(
+    owner_id: &AccountId,
+    royalty: &HashMap<AccountId, u32>,
+    balance: Balance,
+    max_len_payout: u32,
+) -> Payout {
+    //make sure we're not paying out to too many people (GAS limits this)
+    if royalty.len() as u32 + 1 > max_len_payout {
+        env::panic_str("ERR_PAYOUT_TOO_LONG");
+    }
+
+    //keep track of the total perpetual royalties paid to accounts other than the owner
+    let mut total_perpetual = 0;
+    let mut payout = Payout {
+        payout: HashMap::new(),
+    };
+
+    //go through each key and value in the royalty object
+    for (account_id, percentage) in royalty.iter() {
+        //only insert into the payout if the key isn't the token owner (we add their payout at the end)
+        if account_id != owner_id {
+            payout
+                .payout
+                .insert(account_id.clone(), royalty_to_payout(*percentage, balance));
+            total_perpetual += *percentage;
+        }
+    }
+
+    //payout to the owner, who gets 100% - the total perpetual royalties
+    payout
+        .payout
+        .insert(owner_id.clone(), royalty_to_payout(10_000 - total_perpetual, balance));
+
+    payout
+}
+
+#[near_bindgen]
+impl NonFungibleTokenRoyalty for Contract {
+    //calculates the payout for a token given the passed in balance. This is a view method
+    fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout {
+        //get the token object
+        let token = self
+            .token_by_id
+            .get(&token_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NO_SUCH_TOKEN"));
+
+        compute_payout(&token.owner_id, &token.royalty, balance.0, max_len_payout)
+    }
+
+    //transfers the token to the receiver ID and returns the payout object that should be paid given the passed in balance
+    #[payable]
+    fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: u64,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+    ) -> Payout {
+        //assert that the user attached 1 yocto NEAR for security reasons
+        assert_one_yocto();
+        //get the sender ID
+        let sender_id = env::predecessor_account_id();
+
+        //transfer the token to the passed in receiver and get the previous token object back
+        let previous_token =
+            self.internal_transfer(&sender_id, &receiver_id, &token_id, Some(approval_id), memo);
+
+        //the transfer cleared the approvals, so refund their storage to the previous owner who paid for it
+        refund_approved_account_ids(previous_token.owner_id.clone(), &previous_token.approved_account_ids);
+
+        //the payout goes to the previous owner, since they are the one selling the token
+        compute_payout(
+            &previous_token.owner_id,
+            &previous_token.royalty,
+            balance.0,
+            max_len_payout,
+        )
+    }
+}
@@ -0,0 +1,1316 @@
+/*
+ * unit tests for the NFT contract
+ * execute them running the command:
+ * cargo test --package nft_simple -- --nocapture
+ */
+use crate::nft_core::NonFungibleTokenCore;
+use crate::*;
+use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+use near_sdk::{testing_env, PromiseResult, RuntimeFeesConfig, VMConfig, VMContext};
+
+// attach 0.1 NEAR to every mint, which is plenty to cover the storage of a token
+const MINT_STORAGE_COST: Balance = 100_000_000_000_000_000_000_000;
+// attach 0.01 NEAR to every approval, which is plenty to cover the storage of an approved account
+const APPROVAL_STORAGE_COST: Balance = 10_000_000_000_000_000_000_000;
+
+// define fake accounts
+fn bbox() -> AccountId {
+    "bbox.testnet".parse().unwrap()
+}
+
+fn doomslug() -> AccountId {
+    "doomslug.testnet".parse().unwrap()
+}
+
+fn spensa() -> AccountId {
+    "spensa.testnet".parse().unwrap()
+}
+
+// same length as 'doomslug.testnet', so that minting to either takes up the same storage
+fn raindrop() -> AccountId {
+    "raindrop.testnet".parse().unwrap()
+}
+
+// set up a mock context with a given predecessor and attached deposit
+// 'bbox.testnet' is both the account the contract is deployed to and its owner
+fn get_context(predecessor: AccountId, deposit: Balance) -> VMContext {
+    VMContextBuilder::new()
+        .current_account_id(bbox())
+        .predecessor_account_id(predecessor)
+        .attached_deposit(deposit)
+        .build()
+}
+
+// token metadata with a custom title, all optional fields left empty
+fn sample_metadata(title: &str) -> TokenMetadata {
+    TokenMetadata {
+        title: title.to_string(),
+        description: "Credit score attestation".to_string(),
+        media: "https://example.com/score.png".to_string(),
+        media_hash: None,
+        copies: Some(1),
+        issued_at: 0,
+        expires_at: None,
+        starts_at: None,
+        updated_at: None,
+        extra: None,
+        reference: None,
+        reference_hash: None,
+    }
+}
+
+// contract metadata for deployments that don't use the default one
+fn sample_contract_metadata() -> NFTContractMetadata {
+    NFTContractMetadata {
+        spec: "nft-1.0.0".to_string(),
+        name: "Credit score NFT".to_string(),
+        symbol: "SCORE".to_string(),
+        icon: None,
+        base_uri: None,
+        reference: None,
+        reference_hash: None,
+    }
+}
+
+// mint a token as the contract owner, attaching enough deposit for storage
+fn mint(contract: &mut Contract, token_id: &str, receiver_id: AccountId, soulbound: bool) {
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint(
+        token_id.to_string(),
+        sample_metadata(token_id),
+        receiver_id,
+        Some(soulbound),
+        None,
+        None,
+        None,
+    );
+}
+
+// mock the contract calling itself back with the given deposit and promise result
+fn set_callback_result(deposit: Balance, result: PromiseResult) {
+    testing_env!(
+        get_context(bbox(), deposit),
+        VMConfig::test(),
+        RuntimeFeesConfig::test(),
+        Default::default(),
+        vec![result],
+    );
+}
+
+fn setup_contract() -> Contract {
+    testing_env!(get_context(bbox(), 0));
+    Contract::new_default_meta(bbox())
+}
+
+#[test]
+fn enumerate_soulbound_tokens() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), true);
+    mint(&mut contract, "token-2", spensa(), false);
+    mint(&mut contract, "token-3", spensa(), true);
+
+    let soulbound = contract.nft_soulbound_tokens(None, None);
+    let ids: Vec<TokenId> = soulbound.iter().map(|t| t.token_id.clone()).collect();
+    assert_eq!(vec!["token-1".to_string(), "token-3".to_string()], ids);
+
+    // pagination counts soulbound tokens only
+    let page = contract.nft_soulbound_tokens(Some(U128(1)), Some(1));
+    assert_eq!(1, page.len());
+    assert_eq!("token-3", page[0].token_id);
+}
+
+#[test]
+fn mint_token_to_receiver() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    // the token is indexed by id, with its metadata, and under its owner
+    let token = contract.token_by_id.get(&"token-1".to_string()).unwrap();
+    assert_eq!(doomslug(), token.owner_id);
+    assert!(contract.token_metadata_by_id.get(&"token-1".to_string()).is_some());
+    let owned = contract.tokens_per_owner.get(&doomslug()).unwrap();
+    assert!(owned.contains(&"token-1".to_string()));
+    assert!(contract.tokens_per_owner.get(&spensa()).is_none());
+}
+
+#[test]
+#[should_panic(expected = "ERR_TOKEN_EXISTS")]
+fn mint_existing_token_id() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    mint(&mut contract, "token-1", spensa(), false);
+}
+
+#[test]
+fn view_minted_token() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", spensa(), false);
+
+    let token = contract.nft_token("token-1".to_string()).unwrap();
+    assert_eq!("token-1", token.token_id);
+    assert_eq!(spensa(), token.owner_id);
+    assert_eq!("token-1", token.metadata.title);
+    assert_eq!("Credit score attestation", token.metadata.description);
+    assert!(contract.nft_token("token-2".to_string()).is_none());
+}
+
+#[test]
+fn transfer_token() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, Some("gift".to_string()));
+
+    assert_eq!(spensa(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+    assert!(contract.tokens_per_owner.get(&doomslug()).is_none());
+    assert!(contract.tokens_per_owner.get(&spensa()).unwrap().contains(&"token-1".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn transfer_token_not_owned() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(spensa(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+fn transfer_token_without_yocto() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 0));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, None);
+}
+
+#[test]
+fn transfer_call_reverted_by_receiver() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer_call(spensa(), "token-1".to_string(), None, None, "".to_string());
+    assert_eq!(spensa(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+
+    // the receiver's nft_on_transfer returned true, so the token goes back to its previous owner
+    set_callback_result(0, PromiseResult::Successful(b"true".to_vec()));
+    assert!(!contract.nft_resolve_transfer(doomslug(), spensa(), "token-1".to_string(), HashMap::new()));
+    assert_eq!(doomslug(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+    assert!(contract.tokens_per_owner.get(&doomslug()).unwrap().contains(&"token-1".to_string()));
+    assert!(contract.tokens_per_owner.get(&spensa()).is_none());
+}
+
+#[test]
+fn transfer_emits_nft_transfer_event() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, Some("gift".to_string()));
+
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    let event: near_sdk::serde_json::Value =
+        near_sdk::serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!("nft_transfer", event["event"]);
+    assert_eq!("doomslug.testnet", event["data"][0]["old_owner_id"]);
+    assert_eq!("spensa.testnet", event["data"][0]["new_owner_id"]);
+    assert_eq!(near_sdk::serde_json::json!(["token-1"]), event["data"][0]["token_ids"]);
+    assert_eq!("gift", event["data"][0]["memo"]);
+    // the owner transferred the token themselves
+    assert!(event["data"][0].get("authorized_id").is_none());
+}
+
+#[test]
+fn reverted_transfer_call_emits_nft_transfer_event() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer_call(spensa(), "token-1".to_string(), None, None, "".to_string());
+    set_callback_result(0, PromiseResult::Successful(b"true".to_vec()));
+    contract.nft_resolve_transfer(doomslug(), spensa(), "token-1".to_string(), HashMap::new());
+
+    // the token going back to its previous owner is a transfer too
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    let event: near_sdk::serde_json::Value =
+        near_sdk::serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!("nft_transfer", event["event"]);
+    assert_eq!("spensa.testnet", event["data"][0]["old_owner_id"]);
+    assert_eq!("doomslug.testnet", event["data"][0]["new_owner_id"]);
+}
+
+#[test]
+fn transfer_call_kept_by_receiver() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer_call(spensa(), "token-1".to_string(), None, None, "".to_string());
+
+    // the receiver's nft_on_transfer returned false, so the token stays with the receiver
+    set_callback_result(0, PromiseResult::Successful(b"false".to_vec()));
+    assert!(contract.nft_resolve_transfer(doomslug(), spensa(), "token-1".to_string(), HashMap::new()));
+    assert_eq!(spensa(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+    assert!(contract.tokens_per_owner.get(&spensa()).unwrap().contains(&"token-1".to_string()));
+    assert!(contract.tokens_per_owner.get(&doomslug()).is_none());
+}
+
+#[test]
+fn check_transferable_tokens() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    mint(&mut contract, "token-2", doomslug(), true);
+
+    assert!(contract.is_transferable("token-1".to_string()));
+    assert!(!contract.is_transferable("token-2".to_string()));
+    assert!(!contract.is_transferable("token-3".to_string()));
+}
+
+#[test]
+fn expired_token_is_not_transferable() {
+    let mut contract = setup_contract();
+    let mut metadata = sample_metadata("token-1");
+    // expires one second after the unix epoch, in milliseconds
+    metadata.expires_at = Some(1_000);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), metadata, doomslug(), Some(false), None, None, None);
+    assert!(contract.is_transferable("token-1".to_string()));
+
+    // two seconds after the unix epoch, in nanoseconds
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(bbox())
+        .predecessor_account_id(doomslug())
+        .block_timestamp(2_000_000_000)
+        .build());
+    assert!(!contract.is_transferable("token-1".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "ERR_SOULBOUND_TOKEN")]
+fn transfer_soulbound_token() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), true);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "ERR_SOULBOUND_TOKEN")]
+fn transfer_error_agrees_with_is_transferable() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), true);
+    assert!(!contract.is_transferable("token-1".to_string()));
+
+    // an account that isn't approved gets the reason is_transferable reported, not ERR_NOT_OWNER
+    testing_env!(get_context(spensa(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, None);
+}
+
+#[test]
+fn burn_token() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 0));
+    contract.nft_burn("token-1".to_string());
+
+    // the token vanished from all three collections
+    assert!(contract.token_by_id.get(&"token-1".to_string()).is_none());
+    assert!(contract.token_metadata_by_id.get(&"token-1".to_string()).is_none());
+    assert!(contract.tokens_per_owner.get(&doomslug()).is_none());
+
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    assert!(logs[0].starts_with("EVENT_JSON:"));
+    assert!(logs[0].contains("\"event\":\"nft_burn\""));
+    assert!(logs[0].contains("token-1"));
+}
+
+#[test]
+fn burn_refunds_storage_to_minter() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    // the holder burns the token, but the contract owner paid for its storage
+    testing_env!(get_context(doomslug(), 0));
+    contract.nft_burn("token-1".to_string());
+
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(bbox(), receipts[0].receiver_id);
+}
+
+#[test]
+fn contract_owner_burns_token() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), true);
+
+    testing_env!(get_context(bbox(), 0));
+    contract.nft_burn("token-1".to_string());
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_AUTHORIZED")]
+fn burn_token_not_owned() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(spensa(), 0));
+    contract.nft_burn("token-1".to_string());
+}
+
+
+#[test]
+fn paginate_tokens() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    mint(&mut contract, "token-2", spensa(), false);
+    mint(&mut contract, "token-3", doomslug(), false);
+
+    // defaults return every token
+    assert_eq!(3, contract.nft_tokens(None, None).len());
+
+    // exactly the second token
+    let page = contract.nft_tokens(Some(U128(1)), Some(1));
+    assert_eq!(1, page.len());
+    assert_eq!("token-2", page[0].token_id);
+    assert_eq!(spensa(), page[0].owner_id);
+
+    // indices past the end return an empty page
+    assert!(contract.nft_tokens(Some(U128(10)), Some(5)).is_empty());
+}
+
+#[test]
+fn burn_expired_tokens() {
+    let mut contract = setup_contract();
+    let mut expiring = sample_metadata("token-1");
+    // expires one second after the unix epoch, in milliseconds
+    expiring.expires_at = Some(1_000);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), expiring, doomslug(), Some(false), None, None, None);
+    mint(&mut contract, "token-2", doomslug(), false);
+    mint(&mut contract, "token-3", spensa(), false);
+
+    // two seconds after the unix epoch, in nanoseconds
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(bbox())
+        .predecessor_account_id(bbox())
+        .block_timestamp(2_000_000_000)
+        .build());
+    assert_eq!(1, contract.burn_expired(None, None));
+
+    // only the expired token is gone
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+    assert!(contract.nft_token("token-2".to_string()).is_some());
+    assert!(contract.nft_token("token-3".to_string()).is_some());
+    assert_eq!(1, contract.tokens_per_owner.get(&doomslug()).unwrap().len());
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    assert!(logs[0].contains("nft_burn"));
+}
+
+
+#[test]
+fn list_tokens_for_owner() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    mint(&mut contract, "token-2", spensa(), false);
+    mint(&mut contract, "token-3", doomslug(), false);
+
+    assert_eq!(U128(2), contract.nft_supply_for_owner(doomslug()));
+    assert_eq!(U128(0), contract.nft_supply_for_owner(bbox()));
+
+    let ids: Vec<TokenId> = contract
+        .nft_tokens_for_owner(doomslug(), None, None)
+        .into_iter()
+        .map(|t| t.token_id)
+        .collect();
+    assert_eq!(vec!["token-1".to_string(), "token-3".to_string()], ids);
+    assert_eq!(1, contract.nft_tokens_for_owner(doomslug(), Some(U128(1)), Some(10)).len());
+    assert!(contract.nft_tokens_for_owner(bbox(), None, None).is_empty());
+}
+
+#[test]
+fn report_total_supply() {
+    let mut contract = setup_contract();
+    assert_eq!(U128(0), contract.nft_total_supply());
+
+    mint(&mut contract, "token-1", doomslug(), false);
+    assert_eq!(U128(1), contract.nft_total_supply());
+
+    mint(&mut contract, "token-2", spensa(), false);
+    mint(&mut contract, "token-3", spensa(), false);
+    assert_eq!(U128(3), contract.nft_total_supply());
+}
+
+#[test]
+fn view_contract_metadata() {
+    let contract = setup_contract();
+    let metadata = contract.nft_metadata();
+    assert_eq!("nft_1.0.0", metadata.spec);
+    assert_eq!("Credit score NFT minter", metadata.name);
+    assert_eq!("Balloonbox", metadata.symbol);
+    assert!(metadata.icon.is_none());
+}
+
+#[test]
+fn tokens_are_soulbound_by_default() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+    assert!(contract.token_by_id.get(&"token-1".to_string()).unwrap().soulbound);
+    assert!(!contract.is_transferable("token-1".to_string()));
+
+    // soulbound tokens can still be burned
+    testing_env!(get_context(doomslug(), 0));
+    contract.nft_burn("token-1".to_string());
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+}
+
+#[test]
+#[should_panic(expected = "ERR_SOULBOUND_TOKEN")]
+fn transfer_call_soulbound_token() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer_call(spensa(), "token-1".to_string(), None, None, "".to_string());
+}
+
+#[test]
+fn override_soulbound_default() {
+    testing_env!(get_context(bbox(), 0));
+    let mut contract = Contract::new(bbox(), sample_contract_metadata(), Some(false), None);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, None);
+    assert_eq!(spensa(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+}
+
+#[test]
+fn round_trip_score_through_metadata() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(720), None, None);
+    mint(&mut contract, "token-2", doomslug(), true);
+
+    let metadata = contract.nft_token("token-1".to_string()).unwrap().metadata;
+    assert_eq!(Some("{\"score\":720}".to_string()), metadata.extra);
+    assert_eq!(Some(720), contract.token_score("token-1".to_string()));
+    // tokens minted without a score, and unknown tokens, have no score
+    assert_eq!(None, contract.token_score("token-2".to_string()));
+    assert_eq!(None, contract.token_score("token-3".to_string()));
+}
+
+#[test]
+fn mint_if_score_qualifies() {
+    let mut contract = setup_contract();
+    set_callback_result(MINT_STORAGE_COST, PromiseResult::Successful(b"720".to_vec()));
+    assert!(contract.resolve_mint_if_qualified("token-1".to_string(), doomslug(), 700));
+
+    let token = contract.nft_token("token-1".to_string()).unwrap();
+    assert_eq!(doomslug(), token.owner_id);
+    assert_eq!(Some(720), contract.token_score("token-1".to_string()));
+}
+
+#[test]
+fn skip_mint_if_score_too_low() {
+    let mut contract = setup_contract();
+    set_callback_result(MINT_STORAGE_COST, PromiseResult::Successful(b"650".to_vec()));
+    assert!(!contract.resolve_mint_if_qualified("token-1".to_string(), doomslug(), 700));
+
+    // no token, and the deposit goes back to the account that attached it
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+    assert_eq!(vec!["ERR_SCORE_BELOW_THRESHOLD".to_string()], get_logs());
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+}
+
+#[test]
+fn skip_mint_if_score_query_failed() {
+    let mut contract = setup_contract();
+    set_callback_result(MINT_STORAGE_COST, PromiseResult::Failed);
+    assert!(!contract.resolve_mint_if_qualified("token-1".to_string(), doomslug(), 700));
+
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+    assert_eq!(vec!["ERR_SCORE_QUERY_FAILED".to_string()], get_logs());
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+}
+
+#[test]
+fn skip_mint_if_token_minted_in_the_meantime() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", spensa(), false);
+    set_callback_result(MINT_STORAGE_COST, PromiseResult::Successful(b"720".to_vec()));
+    assert!(!contract.resolve_mint_if_qualified("token-1".to_string(), doomslug(), 700));
+
+    // the existing token is left alone, and the deposit goes back to the receiver
+    assert_eq!(spensa(), contract.nft_token("token-1".to_string()).unwrap().owner_id);
+    assert_eq!(vec!["ERR_TOKEN_EXISTS".to_string()], get_logs());
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+}
+
+#[test]
+fn skip_mint_if_deposit_too_low() {
+    let mut contract = setup_contract();
+    set_callback_result(1, PromiseResult::Successful(b"720".to_vec()));
+    assert!(!contract.resolve_mint_if_qualified("token-1".to_string(), doomslug(), 700));
+
+    // the mint is rolled back rather than kept for free
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+    assert_eq!(0, contract.mints_on_day(0));
+    let logs = get_logs();
+    assert!(logs[1].contains("\"event\":\"nft_burn\""));
+    assert_eq!("ERR_DEPOSIT_TOO_LOW", logs[2]);
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_TOKEN_EXISTS")]
+fn mint_if_qualified_existing_token_id() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.set_score_oracle(spensa(), 700);
+    mint(&mut contract, "token-1", spensa(), false);
+
+    // rejected before the oracle is even queried
+    testing_env!(get_context(doomslug(), MINT_STORAGE_COST));
+    contract.mint_if_qualified("token-1".to_string());
+}
+
+#[test]
+fn mint_if_qualified_queries_trusted_oracle() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.set_score_oracle(spensa(), 700);
+    assert_eq!(Some(spensa()), contract.get_score_oracle());
+    assert_eq!(700, contract.get_min_mint_score());
+    let logs = get_logs();
+    assert!(logs[0].contains("\"event\":\"score_oracle_update\""));
+    assert!(logs[0].contains("\"oracle\":\"spensa.testnet\""));
+    assert!(logs[0].contains("\"min_score\":700"));
+
+    testing_env!(get_context(doomslug(), MINT_STORAGE_COST));
+    contract.mint_if_qualified("token-1".to_string());
+    // the score is queried from the oracle the owner set, not one the caller picked
+    let receipts = get_created_receipts();
+    assert_eq!(spensa(), receipts[0].receiver_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_SCORE_ORACLE")]
+fn mint_if_qualified_without_oracle() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(doomslug(), MINT_STORAGE_COST));
+    contract.mint_if_qualified("token-1".to_string());
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER")]
+fn only_owner_sets_score_oracle() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(doomslug(), 0));
+    contract.set_score_oracle(doomslug(), 0);
+}
+
+#[test]
+fn authorized_minter_mints() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.add_minter(spensa());
+    assert_eq!(vec![spensa()], contract.get_minters());
+    let logs = get_logs();
+    assert!(logs[0].contains("\"event\":\"minter_update\""));
+    // the contract's own events aren't passed off as NFT standard ones
+    assert!(logs[0].contains("\"standard\":\"credit_score_nft\""));
+    assert!(logs[0].contains("\"authorized\":true"));
+
+    testing_env!(get_context(spensa(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+    assert_eq!(doomslug(), contract.nft_token("token-1".to_string()).unwrap().owner_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER_CAN_MINT")]
+fn random_account_cannot_mint() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(doomslug(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER_CAN_MINT")]
+fn removed_minter_cannot_mint() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.add_minter(spensa());
+    contract.remove_minter(spensa());
+
+    testing_env!(get_context(spensa(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+}
+
+#[test]
+fn mint_with_exact_deposit() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    let initial_storage_usage = env::storage_usage();
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+    let storage_used = env::storage_usage() - initial_storage_usage;
+
+    // an identical mint covered by the exact deposit doesn't refund anything
+    let exact_deposit = env::storage_byte_cost() * Balance::from(storage_used);
+    testing_env!(get_context(bbox(), exact_deposit));
+    contract.nft_mint("token-2".to_string(), sample_metadata("token-2"), raindrop(), None, None, None, None);
+    assert!(get_created_receipts().is_empty());
+}
+
+#[test]
+fn mint_with_oversized_deposit() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+
+    // the surplus is refunded to the minter with a transfer promise
+    assert_eq!(1, get_created_receipts().len());
+}
+
+#[test]
+#[should_panic(expected = "ERR_DEPOSIT_TOO_LOW")]
+fn mint_without_deposit() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+}
+
+
+#[test]
+fn approve_account_for_token() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), APPROVAL_STORAGE_COST));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+    assert!(contract.nft_is_approved_for_id("token-1".to_string(), spensa(), None));
+    assert!(contract.nft_is_approved_for_id("token-1".to_string(), spensa(), Some(0)));
+    assert!(!contract.nft_is_approved_for_id("token-1".to_string(), spensa(), Some(1)));
+    assert!(!contract.nft_is_approved_for_id("token-1".to_string(), raindrop(), None));
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_revoke("token-1".to_string(), spensa());
+    assert!(!contract.nft_is_approved_for_id("token-1".to_string(), spensa(), None));
+}
+
+#[test]
+#[should_panic(expected = "ERR_DEPOSIT_TOO_LOW")]
+fn approve_without_storage_deposit() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+}
+
+#[test]
+fn approve_refunds_surplus_deposit() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), APPROVAL_STORAGE_COST));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+}
+
+#[test]
+fn revoke_refunds_approval_storage() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    testing_env!(get_context(doomslug(), APPROVAL_STORAGE_COST));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+    contract.nft_approve("token-1".to_string(), raindrop(), None);
+
+    // the released storage goes back to the owner, who paid for it
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_revoke("token-1".to_string(), spensa());
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_revoke_all("token-1".to_string());
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+    assert!(!contract.nft_is_approved_for_id("token-1".to_string(), raindrop(), None));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn approve_token_not_owned() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(spensa(), 1));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+}
+
+#[test]
+fn approved_transfer_clears_approvals() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), APPROVAL_STORAGE_COST));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+    contract.nft_approve("token-1".to_string(), raindrop(), None);
+
+    // the approved account moves the token on the owner's behalf
+    testing_env!(get_context(spensa(), 1));
+    contract.nft_transfer(raindrop(), "token-1".to_string(), Some(0), None);
+    assert!(get_logs()[0].contains("\"authorized_id\":\"spensa.testnet\""));
+    // the storage of the cleared approvals goes back to the previous owner
+    let receipts = get_created_receipts();
+    assert_eq!(1, receipts.len());
+    assert_eq!(doomslug(), receipts[0].receiver_id);
+
+    let token = contract.token_by_id.get(&"token-1".to_string()).unwrap();
+    assert_eq!(raindrop(), token.owner_id);
+    assert!(token.approved_account_ids.is_empty());
+    assert_eq!(2, token.next_approval_id);
+    assert!(!contract.nft_is_approved_for_id("token-1".to_string(), spensa(), None));
+}
+
+// mint a transferable token to 'doomslug.testnet' with the given perpetual royalties
+fn mint_with_royalties(contract: &mut Contract, royalties: Vec<(AccountId, u32)>) {
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint(
+        "token-1".to_string(),
+        sample_metadata("token-1"),
+        doomslug(),
+        Some(false),
+        None,
+        Some(royalties.into_iter().collect()),
+        None,
+    );
+}
+
+#[test]
+fn payout_to_single_royalty_recipient() {
+    let mut contract = setup_contract();
+    // 10% royalty
+    mint_with_royalties(&mut contract, vec![(spensa(), 1_000)]);
+
+    let payout = contract.nft_payout("token-1".to_string(), U128(1_000_000), 10).payout;
+    assert_eq!(2, payout.len());
+    assert_eq!(U128(100_000), payout[&spensa()]);
+    assert_eq!(U128(900_000), payout[&doomslug()]);
+}
+
+#[test]
+fn payout_split_between_two_recipients() {
+    let mut contract = setup_contract();
+    // 10% and 25% royalties
+    mint_with_royalties(&mut contract, vec![(spensa(), 1_000), (raindrop(), 2_500)]);
+
+    // the approved marketplace sells the token to 'bbox.testnet'
+    testing_env!(get_context(doomslug(), APPROVAL_STORAGE_COST));
+    contract.nft_approve("token-1".to_string(), spensa(), None);
+    testing_env!(get_context(spensa(), 1));
+    let payout = contract
+        .nft_transfer_payout(bbox(), "token-1".to_string(), 0, None, U128(1_000_000), 10)
+        .payout;
+    assert_eq!(3, payout.len());
+    assert_eq!(U128(100_000), payout[&spensa()]);
+    assert_eq!(U128(250_000), payout[&raindrop()]);
+    assert_eq!(U128(650_000), payout[&doomslug()]);
+    assert_eq!(bbox(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_ROYALTY_TOO_HIGH")]
+fn mint_with_royalties_above_hundred_percent() {
+    let mut contract = setup_contract();
+    mint_with_royalties(&mut contract, vec![(spensa(), 6_000), (raindrop(), 4_001)]);
+}
+
+#[test]
+fn group_tokens_by_score_tier() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-2".to_string(), sample_metadata("token-2"), spensa(), None, Some(760), None, None);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-3".to_string(), sample_metadata("token-3"), raindrop(), None, Some(320), None, None);
+
+    let bronze: Vec<TokenId> = contract
+        .nft_tokens_by_type("bronze".to_string(), None, None)
+        .into_iter()
+        .map(|token| token.token_id)
+        .collect();
+    assert_eq!(vec!["token-1".to_string(), "token-3".to_string()], bronze);
+    let gold: Vec<TokenId> = contract
+        .nft_tokens_by_type("gold".to_string(), None, None)
+        .into_iter()
+        .map(|token| token.token_id)
+        .collect();
+    assert_eq!(vec!["token-2".to_string()], gold);
+    assert!(contract.nft_tokens_by_type("silver".to_string(), None, None).is_empty());
+
+    assert_eq!(Some("gold".to_string()), contract.token_type("token-2".to_string()));
+    assert_eq!(None, contract.token_type("token-4".to_string()));
+
+    // burning a token takes it out of its type
+    testing_env!(get_context(doomslug(), 0));
+    contract.nft_burn("token-1".to_string());
+    assert_eq!(1, contract.nft_tokens_by_type("bronze".to_string(), None, None).len());
+}
+
+#[test]
+#[should_panic(expected = "ERR_TOKEN_TYPE_LOCKED")]
+fn mint_locked_token_type() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.lock_token_type("gold".to_string());
+
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(760), None, None);
+}
+
+#[test]
+fn mint_after_unlocking_token_type() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.lock_token_type("gold".to_string());
+    assert_eq!(vec!["gold".to_string()], contract.get_locked_token_types());
+
+    // other tiers can still be minted
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+
+    testing_env!(get_context(bbox(), 0));
+    contract.unlock_token_type("gold".to_string());
+    assert!(contract.get_locked_token_types().is_empty());
+    let logs = get_logs();
+    assert!(logs[0].contains("\"event\":\"token_type_lock_update\""));
+    assert!(logs[0].contains("\"locked\":false"));
+
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-2".to_string(), sample_metadata("token-2"), spensa(), None, Some(760), None, None);
+    assert_eq!(Some("gold".to_string()), contract.token_type("token-2".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER")]
+fn only_owner_locks_token_type() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(doomslug(), 0));
+    contract.lock_token_type("gold".to_string());
+}
+
+#[test]
+fn owner_updates_token_metadata() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+
+    // the score moved up a tier
+    let mut metadata = sample_metadata("Silver credit score");
+    metadata.extra = Some("{\"score\":650}".to_string());
+    testing_env!(get_context(bbox(), 0));
+    contract.update_token_metadata("token-1".to_string(), metadata);
+
+    let token = contract.nft_token("token-1".to_string()).unwrap();
+    assert_eq!("Silver credit score", token.metadata.title);
+    assert_eq!(Some(650), contract.token_score("token-1".to_string()));
+    assert_eq!(Some("silver".to_string()), contract.token_type("token-1".to_string()));
+    assert!(contract.nft_tokens_by_type("bronze".to_string(), None, None).is_empty());
+
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    assert!(logs[0].contains("\"event\":\"nft_metadata_update\""));
+    assert!(logs[0].contains("\"standard\":\"nep171\",\"version\":\"1.1.0\""));
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER")]
+fn only_owner_updates_token_metadata() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    testing_env!(get_context(doomslug(), 0));
+    contract.update_token_metadata("token-1".to_string(), sample_metadata("token-1"));
+}
+
+#[test]
+fn look_up_token_owner() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    assert_eq!(Some(doomslug()), contract.whose_token("token-1".to_string()));
+    assert_eq!(None, contract.whose_token("token-2".to_string()));
+}
+
+#[test]
+fn batch_mint_tokens() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 3 * MINT_STORAGE_COST));
+    contract.nft_batch_mint(vec![
+        ("token-1".to_string(), sample_metadata("token-1"), doomslug()),
+        ("token-2".to_string(), sample_metadata("token-2"), spensa()),
+        ("token-3".to_string(), sample_metadata("token-3"), doomslug()),
+    ]);
+
+    assert_eq!(U128(3), contract.nft_total_supply());
+    assert_eq!(U128(2), contract.nft_supply_for_owner(doomslug()));
+    assert_eq!(Some(spensa()), contract.whose_token("token-2".to_string()));
+    assert_eq!(Some(doomslug()), contract.whose_token("token-3".to_string()));
+
+    // a single event lists the tokens minted to each receiver
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    let event: near_sdk::serde_json::Value =
+        near_sdk::serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!("nft_mint", event["event"]);
+    assert_eq!("doomslug.testnet", event["data"][0]["owner_id"]);
+    assert_eq!(near_sdk::serde_json::json!(["token-1", "token-3"]), event["data"][0]["token_ids"]);
+    assert_eq!("spensa.testnet", event["data"][1]["owner_id"]);
+    assert_eq!(near_sdk::serde_json::json!(["token-2"]), event["data"][1]["token_ids"]);
+}
+
+#[test]
+#[should_panic(expected = "ERR_TOKEN_EXISTS")]
+fn batch_mint_existing_token_id() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-2", raindrop(), false);
+
+    testing_env!(get_context(bbox(), 2 * MINT_STORAGE_COST));
+    contract.nft_batch_mint(vec![
+        ("token-1".to_string(), sample_metadata("token-1"), doomslug()),
+        ("token-2".to_string(), sample_metadata("token-2"), spensa()),
+    ]);
+}
+
+#[test]
+fn enumerate_tokens_in_score_range() {
+    let mut contract = setup_contract();
+    for (k, score) in [400, 650, 800].iter().enumerate() {
+        let token_id = format!("token-{}", k + 1);
+        testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+        contract.nft_mint(token_id.clone(), sample_metadata(&token_id), doomslug(), None, Some(*score), None, None);
+    }
+    // a token without a score is never in range
+    mint(&mut contract, "token-4", doomslug(), false);
+
+    let in_range: Vec<TokenId> = contract
+        .nft_tokens_in_score_range(600, 900, None, None)
+        .into_iter()
+        .map(|token| token.token_id)
+        .collect();
+    assert_eq!(vec!["token-2".to_string(), "token-3".to_string()], in_range);
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_RANGE")]
+fn enumerate_tokens_in_inverted_score_range() {
+    let contract = setup_contract();
+    contract.nft_tokens_in_score_range(900, 600, None, None);
+}
+
+#[test]
+#[should_panic(expected = "ERR_CONTRACT_PAUSED")]
+fn mint_while_paused() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 0));
+    contract.pause();
+    mint(&mut contract, "token-1", doomslug(), false);
+}
+
+#[test]
+#[should_panic(expected = "ERR_CONTRACT_PAUSED")]
+fn transfer_while_paused() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    testing_env!(get_context(bbox(), 0));
+    contract.pause();
+
+    testing_env!(get_context(doomslug(), 1));
+    contract.nft_transfer(spensa(), "token-1".to_string(), None, None);
+}
+
+#[test]
+fn mint_after_unpausing() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    testing_env!(get_context(bbox(), 0));
+    contract.pause();
+    assert!(get_logs()[0].contains("\"event\":\"contract_pause_update\""));
+
+    // views keep working while paused
+    assert!(contract.is_paused());
+    assert!(contract.nft_token("token-1".to_string()).is_some());
+    assert_eq!("nft_1.0.0", contract.nft_metadata().spec);
+    assert!(!contract.is_transferable("token-1".to_string()));
+
+    testing_env!(get_context(bbox(), 0));
+    contract.unpause();
+    mint(&mut contract, "token-2", doomslug(), false);
+    assert_eq!(U128(2), contract.nft_total_supply());
+}
+
+#[test]
+#[should_panic(expected = "ERR_OWNER_TOKEN_LIMIT")]
+fn mint_over_owner_token_limit() {
+    testing_env!(get_context(bbox(), 0));
+    let mut contract = Contract::new(bbox(), sample_contract_metadata(), None, Some(1));
+    mint(&mut contract, "token-1", doomslug(), false);
+    // other accounts still get their own token
+    mint(&mut contract, "token-2", spensa(), false);
+    mint(&mut contract, "token-3", doomslug(), false);
+}
+
+#[test]
+fn upsert_mint_inserts_new_token() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, Some(true));
+    assert_eq!(Some(450), contract.token_score("token-1".to_string()));
+    assert_eq!(doomslug(), contract.token_by_id.get(&"token-1".to_string()).unwrap().owner_id);
+}
+
+#[test]
+fn upsert_mint_updates_token_of_same_owner() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(760), None, Some(true));
+
+    // the corrected score replaced the old one and moved the token to its tier
+    assert_eq!(Some(760), contract.token_score("token-1".to_string()));
+    assert_eq!(Some("gold".to_string()), contract.token_type("token-1".to_string()));
+    assert_eq!(1, contract.nft_supply_for_owner(doomslug()).0);
+}
+
+#[test]
+#[should_panic(expected = "ERR_TOKEN_EXISTS")]
+fn upsert_mint_rejects_token_of_other_owner() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), raindrop(), None, Some(760), None, Some(true));
+}
+
+#[test]
+#[should_panic(expected = "ERR_CONTRACT_PAUSED")]
+fn upsert_mint_while_paused() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+    testing_env!(get_context(bbox(), 0));
+    contract.pause();
+
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(760), None, Some(true));
+}
+
+#[test]
+#[should_panic(expected = "ERR_TOKEN_TYPE_LOCKED")]
+fn upsert_mint_into_locked_token_type() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(450), None, None);
+    testing_env!(get_context(bbox(), 0));
+    contract.lock_token_type("gold".to_string());
+
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, Some(760), None, Some(true));
+}
+
+#[test]
+fn list_token_ids_with_pagination() {
+    let mut contract = setup_contract();
+    for token_id in ["token-1", "token-2", "token-3"].iter() {
+        mint(&mut contract, token_id, doomslug(), false);
+    }
+
+    let all = contract.nft_token_ids(None, None);
+    assert_eq!(vec!["token-1".to_string(), "token-2".to_string(), "token-3".to_string()], all);
+    let page = contract.nft_token_ids(Some(U128(1)), Some(1));
+    assert_eq!(vec!["token-2".to_string()], page);
+    assert!(contract.nft_token_ids(Some(U128(3)), None).is_empty());
+}
+
+#[test]
+fn mint_emits_nft_mint_event() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    let event: near_sdk::serde_json::Value =
+        near_sdk::serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!("nep171", event["standard"]);
+    assert_eq!("1.0.0", event["version"]);
+    assert_eq!("nft_mint", event["event"]);
+    assert_eq!("doomslug.testnet", event["data"][0]["owner_id"]);
+    assert_eq!(near_sdk::serde_json::json!(["token-1"]), event["data"][0]["token_ids"]);
+}
+
+#[test]
+fn burn_emits_nft_burn_event() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    testing_env!(get_context(doomslug(), 0));
+    contract.nft_burn("token-1".to_string());
+
+    let logs = get_logs();
+    assert_eq!(1, logs.len());
+    let event: near_sdk::serde_json::Value =
+        near_sdk::serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!("nep171", event["standard"]);
+    assert_eq!("nft_burn", event["event"]);
+    assert_eq!(near_sdk::serde_json::json!(["token-1"]), event["data"][0]["token_ids"]);
+}
+
+#[test]
+fn count_mints_per_day() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), false);
+    mint(&mut contract, "token-2", raindrop(), false);
+
+    // the mocked block timestamp is 0, i.e. day 0
+    assert_eq!(2, contract.mints_on_day(0));
+    assert_eq!(0, contract.mints_on_day(1));
+}
+
+#[test]
+fn init_with_data_uri_icon() {
+    testing_env!(get_context(bbox(), 0));
+    let mut metadata = sample_contract_metadata();
+    metadata.icon = Some("data:image/svg+xml;base64,PHN2Zy8+".to_string());
+    let contract = Contract::new(bbox(), metadata, None, None);
+    assert_eq!(Some("data:image/svg+xml;base64,PHN2Zy8+".to_string()), contract.nft_metadata().icon);
+}
+
+#[test]
+fn init_without_icon() {
+    testing_env!(get_context(bbox(), 0));
+    let contract = Contract::new(bbox(), sample_contract_metadata(), None, None);
+    assert!(contract.nft_metadata().icon.is_none());
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_ICON")]
+fn reject_icon_that_is_not_a_data_uri() {
+    testing_env!(get_context(bbox(), 0));
+    let mut metadata = sample_contract_metadata();
+    metadata.icon = Some("https://example.com/icon.png".to_string());
+    Contract::new(bbox(), metadata, None, None);
+}
+
+#[test]
+fn burn_if_score_dropped_below_threshold() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), true);
+    set_callback_result(0, PromiseResult::Successful(b"450".to_vec()));
+    assert!(contract.resolve_burn_if_below("token-1".to_string(), 500));
+
+    assert!(contract.nft_token("token-1".to_string()).is_none());
+    assert!(get_logs()[0].contains("\"event\":\"nft_burn\""));
+}
+
+#[test]
+fn keep_token_if_score_above_threshold() {
+    let mut contract = setup_contract();
+    mint(&mut contract, "token-1", doomslug(), true);
+    set_callback_result(0, PromiseResult::Successful(b"650".to_vec()));
+    assert!(!contract.resolve_burn_if_below("token-1".to_string(), 500));
+
+    assert!(contract.nft_token("token-1".to_string()).is_some());
+}
+
+#[test]
+fn mint_with_sequential_auto_ids() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    let first = contract.nft_mint_auto(sample_metadata("first"), doomslug(), None, None, None);
+    testing_env!(get_context(bbox(), MINT_STORAGE_COST));
+    let second = contract.nft_mint_auto(sample_metadata("second"), raindrop(), None, None, None);
+
+    assert_eq!("score-0", first);
+    assert_eq!("score-1", second);
+    assert_eq!(raindrop(), contract.nft_token(second).unwrap().owner_id);
+}
+
+#[test]
+fn skip_taken_auto_id() {
+    let mut contract = setup_contract();
+    // an explicit ID colliding with the first derived one
+    mint(&mut contract, "score-1", doomslug(), false);
+    assert_eq!("score-2", contract.next_token_id());
+}
+
+#[test]
+fn hand_over_contract_ownership() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 1));
+    contract.transfer_contract_ownership(spensa());
+    assert_eq!(spensa(), contract.owner_id);
+    let logs = get_logs();
+    let event: near_sdk::serde_json::Value =
+        near_sdk::serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+    assert_eq!("contract_owner_transfer", event["event"]);
+    assert_eq!("bbox.testnet", event["data"][0]["old_owner_id"]);
+    assert_eq!("spensa.testnet", event["data"][0]["new_owner_id"]);
+
+    // the new owner mints
+    testing_env!(get_context(spensa(), MINT_STORAGE_COST));
+    contract.nft_mint("token-1".to_string(), sample_metadata("token-1"), doomslug(), None, None, None, None);
+    assert!(contract.nft_token("token-1".to_string()).is_some());
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER_CAN_MINT")]
+fn former_owner_no_longer_mints() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 1));
+    contract.transfer_contract_ownership(spensa());
+    mint(&mut contract, "token-1", doomslug(), false);
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONLY_OWNER")]
+fn only_owner_transfers_contract_ownership() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(doomslug(), 1));
+    contract.transfer_contract_ownership(doomslug());
+}
+
+#[test]
+#[should_panic(expected = "ERR_SAME_OWNER")]
+fn reject_transfer_to_current_owner() {
+    let mut contract = setup_contract();
+    testing_env!(get_context(bbox(), 1));
+    contract.transfer_contract_ownership(bbox());
+}
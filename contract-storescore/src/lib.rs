@@ -1,11 +1,57 @@
 // Import crates
-use near_sdk::collections::{LookupMap, Vector};
-use near_sdk::{log,
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedSet, Vector};
+use near_sdk::{ext_contract, log,
     borsh::{self, BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
-    AccountId, Gas, PanicOnDefault, BorshStorageKey,
+    AccountId, Gas, PanicOnDefault, BorshStorageKey, Promise, PromiseOrValue, PromiseResult,
 };
 use near_sdk::{env, near_bindgen};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde_json::{self, json};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+// version of the deployed code, taken from Cargo.toml
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+// default name and version of the NEP-297 standard followed by the events of this contract
+const EVENT_STANDARD: &str = "nearoracle";
+const EVENT_VERSION: &str = "1.0.0";
+// maximum number of peer accounts accepted by a single cohort comparison
+const MAX_GROUP_SIZE: usize = 50;
+// maximum number of entries accepted by a single batch call
+const MAX_BATCH_SIZE: usize = 100;
+// maximum number of accounts returned by a single ranking query
+const MAX_RANKING_SIZE: u64 = 100;
+// number of most recent writes, across all users, kept for the activity feed
+const MAX_RECENT_SCORES: u64 = 100;
+// default upper bound on the number of scores stored for each user
+const MAX_SCORES: u16 = 100;
+// default upper bound on the length (in bytes) of a score description
+const MAX_DESC_LEN: u32 = 280;
+// range of the credit scores computed by the oracle
+const MIN_SCORE: u16 = 300;
+const MAX_SCORE: u16 = 900;
+// how long after storing a score a user may still retract it (5 minutes)
+const GRACE_NS: u64 = 5 * 60 * 1_000_000_000;
+// minimum time between two scores of the same user (30 seconds)
+const COOLDOWN_NS: u64 = 30 * 1_000_000_000;
+// storage the protocol charges on top of the key and value of every record
+const STORAGE_BYTES_PER_RECORD: u64 = 40;
+// number of nanoseconds in a day, block timestamps are expressed in nanoseconds
+const NS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+// gas attached to the NFT contract's nft_mint and to the callback resolving it
+const GAS_FOR_NFT_MINT: Gas = Gas(20_000_000_000_000);
+const GAS_FOR_RESOLVE_MINT: Gas = Gas(10_000_000_000_000);
+// gas attached to the NFT contract's nft_supply_for_owner and to the callback merging it into a user's status
+const GAS_FOR_NFT_SUPPLY: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_RESOLVE_STATUS: Gas = Gas(5_000_000_000_000);
+
+// callbacks resolving the cross-contract calls made to the NFT contract
+#[ext_contract(ext_self)]
+pub trait MintCallback {
+    fn on_score_nft_minted(&mut self, account_id: String, token_id: String) -> bool;
+    fn on_user_status(&self, score: u16) -> UserStatus;
+}
 
 // --------------------------------------------------------------------- //
 //                          Define main objects                          //
@@ -16,17 +62,119 @@ use near_sdk::{env, near_bindgen};
 pub struct State {
     pub user_count: u64,
     pub score_count: u64,
+    // id handed out to the next stored score
+    pub next_score_id: u64,
+}
+
+// on-chain struct describing the settings chosen when the contract got initialized
+// omitted fields fall back to their default value when passed in as JSON
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(default)]
+pub struct Config {
+    // maximum number of scores stored for each user
+    pub max_scores: u16,
+    // drop the oldest score rather than panicking when a user's history is full
+    pub rolling_window: bool,
+    // account of the NFT contract minting credit-score attestations, if any
+    pub nft_contract: Option<AccountId>,
+    // minimum score for which an attestation NFT gets minted automatically, if any
+    pub auto_mint_threshold: Option<u16>,
+    // maximum length (in bytes) of a score description
+    pub max_desc_len: u32,
+    // whether the owner may wipe all score histories, meant for testnet deployments only
+    pub allow_reset: bool,
+    // minimum deposit (in yoctoNEAR) attached to store_score, 0 leaving writes free aside from gas
+    pub min_deposit: U128,
+    // NEP-297 standard name of the emitted events, letting white-label deployments use their own
+    pub event_standard: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_scores: MAX_SCORES,
+            rolling_window: false,
+            nft_contract: None,
+            auto_mint_threshold: None,
+            max_desc_len: MAX_DESC_LEN,
+            allow_reset: false,
+            min_deposit: U128(0),
+            event_standard: EVENT_STANDARD.to_string(),
+        }
+    }
 }
 
 // off-chain struct returning the contract state in a human-readable format
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct ContractState {
+    version: String,
     owner: String,
     timestamp: u64,
     size_now: u64,
     user_count: u64,
     score_count: u64,
+    // average number of scores per user in basis points, e.g. 20000 for 2.0 scores per user
+    scores_per_user: u64,
+}
+
+// off-chain struct breaking the contract storage down, all figures are approximate (in bytes)
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageReport {
+    // score histories, keys included
+    records: u64,
+    // the counters kept in the contract state
+    counters: u64,
+    // everything else: contract code, settings, indexes and per-record trie overhead
+    overhead: u64,
+    // total storage used by the contract
+    total: u64,
+}
+
+// off-chain struct summarizing whether the contract's bookkeeping is consistent
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HealthReport {
+    ok: bool,
+    // a description of every invariant that doesn't hold
+    violations: Vec<String>,
+}
+
+// off-chain struct estimating the storage a new score takes up, before the user signs
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CostEstimate {
+    // estimated storage in bytes, excluding the description
+    bytes: u64,
+    // deposit covering those bytes at the current storage price, in yoctoNEAR
+    deposit: U128,
+}
+
+// outcome of a dry run of store_score, explaining why it would fail if it would
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ValidationResult {
+    valid: bool,
+    reason: Option<String>,
+}
+
+// off-chain struct describing how the oracle is wired to the NFT contract
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntegrationStatus {
+    nft_contract: Option<AccountId>,
+    auto_mint_enabled: bool,
+    auto_mint_threshold: Option<u16>,
+}
+
+// off-chain struct merging a user's latest score with the number of NFTs they hold
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UserStatus {
+    score: u16,
+    nft_count: U128,
 }
 
 // output of the function querying a user's score history
@@ -43,6 +191,24 @@ pub struct OnChainHistory {
     scores: Vec<User>,
 }
 
+// a score of the activity feed, together with the account it belongs to
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RecentScore {
+    account_id: String,
+    score: u16,
+    timestamp: u64,
+}
+
+// an account together with its latest score and how much the score moved
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoredAccount {
+    pub account_id: String,
+    pub score: u16,
+    pub delta: i32,
+}
+
 // was the operation of publishing a score to blockchain successful?
 // the struct below describes the operation outcome
 #[derive(Serialize)]
@@ -51,6 +217,19 @@ pub struct ScoreOutcome {
     gas_used: Gas,
     score_owner: String,
     successful_operation: bool,
+    // timestamp recorded for the score
+    timestamp: u64,
+    // position of the score in the user's score history
+    index: u64,
+}
+
+// off-chain struct reporting an account's storage balance as per NEP-145
+// nothing is locked, so the whole balance is available
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    total: U128,
+    available: U128,
 }
 
 // since with Borsh serialization an enum only takes one byte, let's 
@@ -58,21 +237,46 @@ pub struct ScoreOutcome {
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKey {
     Accounts { account_hash: Vec<u8> },
+    UserMeta,
+    UserCaps,
+    Oracles,
+    OracleSubjects,
+    OracleSubjectsInner { oracle_hash: Vec<u8> },
+    AccountIds,
+    AttestationKeys,
+    Nonces,
+    StorageDeposits,
+    Owners,
+    RecentScores,
+    AttestedAt,
+}
+
+// on-chain struct holding per-user metadata that doesn't belong to any single score
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct UserMeta {
+    pub since: u64,
+    // running sum of the scores in the user's history, so that its average is O(1)
+    pub sum: u64,
 }
 
 // user's score, timestamp, and score description as a struct
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct User {
+    // globally unique id of the score, so that off-chain systems can reference it
+    pub id: u64,
     pub score: u16,
     pub timestamp: u64,
     pub description: Vec<u8>,
+    // the account that wrote the score: the user itself, an oracle or the owner
+    pub issuer: AccountId,
 }
 
 // user's score, timestamp, and score description as an offchain sruct
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Score {
+    pub id: u64,
     pub score: u16,
     pub timestamp: u64,
     pub description: String,
@@ -86,9 +290,31 @@ pub struct Score {
 #[near_bindgen]
 #[derive(PanicOnDefault, BorshDeserialize, BorshSerialize)]
 pub struct Contract {
-    owner_id: AccountId,
+    // the accounts allowed to administer the contract, never empty
+    owners: UnorderedSet<AccountId>,
+    // the owner reported by get_owner and read_state, always one of the owners
+    primary_owner: AccountId,
     records: LookupMap<String, Vector<User>>,
+    // the accounts with a score history, so that they can be iterated over
+    accounts: UnorderedSet<String>,
+    user_meta: LookupMap<String, UserMeta>,
+    user_caps: LookupMap<String, u16>,
+    oracles: LookupSet<AccountId>,
+    // ed25519 public keys whose signed attestations users may submit themselves
+    attestation_keys: LookupSet<Vec<u8>>,
+    oracle_subjects: LookupMap<AccountId, UnorderedSet<String>>,
+    // the last nonce each account submitted a score with, so that client retries can't double-store
+    nonces: LookupMap<String, u64>,
+    // the off-chain timestamp of the latest attestation each account submitted
+    attested_at: LookupMap<String, u64>,
+    // the storage balances accounts registered to pay for their scores' storage (NEP-145)
+    storage_deposits: LookupMap<AccountId, u128>,
+    // ring buffer of the latest writes across all users, overwritten oldest first once full
+    recent_scores: Vector<(String, u16, u64)>,
+    // total number of writes that went through the ring buffer, locating its oldest slot
+    recent_writes: u64,
     contract_state: State,
+    config: Config,
 }
 
 // --------------------------------------------------------------------- //
@@ -97,22 +323,53 @@ pub struct Contract {
 // ----------------------------------------------------------------------//
 #[near_bindgen]
 impl Contract {
-    // initialize the contract
+    // initialize the contract with the default settings
     #[init]
     pub fn new(owner_id: AccountId) -> Self {
+        Self::new_with_config(owner_id, Config::default())
+    }
+
+    // initialize the contract with counters carried over from a prior oracle whose data gets imported
+    #[init]
+    pub fn new_with_counters(owner_id: AccountId, user_count: u64, score_count: u64) -> Self {
+        let mut contract = Self::new_with_config(owner_id, Config::default());
+        contract.contract_state.user_count = user_count;
+        contract.contract_state.score_count = score_count;
+        contract
+    }
+
+    // initialize the contract with custom settings
+    #[init]
+    pub fn new_with_config(owner_id: AccountId, config: Config) -> Self {
         // ensure that state doesn't exist.
         // You should NOT initialize a contract if its state exists already
         assert!(
             !env::state_exists(),
             "ERR_THE_CONTRACT_IS_ALREADY_INITIALIZED"
         );
+        let mut owners = UnorderedSet::new(StorageKey::Owners);
+        owners.insert(&owner_id);
         Self {
-            owner_id,
+            owners,
+            primary_owner: owner_id,
             records: LookupMap::new(b"m"),
+            accounts: UnorderedSet::new(StorageKey::AccountIds),
+            user_meta: LookupMap::new(StorageKey::UserMeta),
+            user_caps: LookupMap::new(StorageKey::UserCaps),
+            oracles: LookupSet::new(StorageKey::Oracles),
+            attestation_keys: LookupSet::new(StorageKey::AttestationKeys),
+            oracle_subjects: LookupMap::new(StorageKey::OracleSubjects),
+            nonces: LookupMap::new(StorageKey::Nonces),
+            attested_at: LookupMap::new(StorageKey::AttestedAt),
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+            recent_scores: Vector::new(StorageKey::RecentScores),
+            recent_writes: 0,
             contract_state: State {
                 user_count: 0u64,
                 score_count: 0u64,
+                next_score_id: 0u64,
             },
+            config,
         }
     }
 
@@ -128,328 +385,3441 @@ impl Contract {
     // either directly or through a promise
     // #[private]
     #[payable]
-    pub fn store_score(&mut self, score: u16, description: String) -> ScoreOutcome {
+    // the nonce must be strictly greater than the one of the caller's previous submission,
+    // so that a retried transaction can't store the same score twice
+    pub fn store_score(&mut self, score: u16, description: String, nonce: u64) -> ScoreOutcome {
         let account_id = String::from(env::predecessor_account_id());
-        let new_score = User {
-            score: score,
-            timestamp: env::block_timestamp(),
-            description: description.as_bytes().to_vec(),
-        };
+        self.assert_fresh_nonce(&account_id, nonce);
+        self.paid_write(env::attached_deposit(), |contract| {
+            let outcome = contract.internal_store_score(account_id.clone(), score, description);
+            contract.nonces.insert(&account_id, &nonce);
+            outcome
+        })
+    }
 
-        let mut success = false;
-        let mappy = self.records.get(&account_id);
-        match mappy {
-            // if it's a new user --> create a brand new vector to store their score
-            None => {
-                log!("{} is a new user", account_id);
-                let mut x = Vector::new(
-                    // Every instance of a persistent collection requires a UNIQUE storage prefix,
-                    // so generate a distinct prefix for every user
-                    StorageKey::Accounts { account_hash: env::sha256(account_id.as_bytes()) }
-                );
-                x.push(&new_score);
-                // update the score count iff you succeeded writing it to blockchain`
-                self.records.insert(&account_id, &x);
-                if self.records.insert(&account_id, &x).is_some() {
-                    self.contract_state.user_count += 1;
-                    self.contract_state.score_count += 1;
-                    success = true;
-                    log!("Score stored successfully to NEAR blockchain");
-                }
+    // store a score attested off-chain: the oracle signs "<contract_id>:<account_id>:<score>:<timestamp>:<nonce>"
+    // with a registered ed25519 key and the user submits it, so that the oracle doesn't pay gas
+    // the contract id binds the attestation to this deployment, and the nonce follows the rules of store_score,
+    // while the timestamp must be newer than the one of the user's previous attestation
+    #[payable]
+    pub fn store_signed_score(
+        &mut self,
+        score: u16,
+        timestamp: u64,
+        nonce: u64,
+        signature: Base64VecU8,
+        pubkey: Base64VecU8,
+    ) -> ScoreOutcome {
+        if !self.attestation_keys.contains(&pubkey.0) {
+            env::panic_str("ERR_UNKNOWN_ATTESTATION_KEY")
+        }
+        let account_id = String::from(env::predecessor_account_id());
+        let message = format!("{}:{}:{}:{}:{}", env::current_account_id(), account_id, score, timestamp, nonce);
+        let verified = ed25519_dalek::PublicKey::from_bytes(&pubkey.0)
+            .ok()
+            .zip(ed25519_dalek::Signature::try_from(&signature.0[..]).ok())
+            .map(|(key, sig)| key.verify_strict(message.as_bytes(), &sig).is_ok())
+            .unwrap_or(false);
+        if !verified {
+            env::panic_str("ERR_BAD_SIGNATURE")
+        }
+        self.assert_fresh_nonce(&account_id, nonce);
+        // off-chain timestamps are only compared with each other, never with block timestamps
+        if let Some(last) = self.attested_at.get(&account_id) {
+            if timestamp <= last {
+                env::panic_str("ERR_STALE_ATTESTATION")
             }
+        }
+        self.paid_write(env::attached_deposit(), |contract| {
+            let outcome = contract.internal_store_score(account_id.clone(), score, String::new());
+            contract.nonces.insert(&account_id, &nonce);
+            contract.attested_at.insert(&account_id, &timestamp);
+            outcome
+        })
+    }
 
-            // if it's a returning user --> append new score to existing vector
-            Some(i) => {
-                log!("{} is a returning user", account_id);
-                let indx = i.len() - 1;
-                if let Some(j) = i.get(indx) {
-                    let timelapsed = new_score.timestamp - j.timestamp;
-                    // if statement w/ 2 conditions: iff there's less than 100 scores, iff last score is 30+ days old
-                    if i.len() < 100 && timelapsed > 30 * u64::pow(10, 9) { // 30 seconds
-                        // && timelapsed > 2592 * u64::pow(10, 12) {  // 30 days
-                        let mut y = i;
-                        y.push(&new_score);
-                        // update the score count iff you succeeded writing it to chain
-                        self.records.insert(&account_id, &y);
-                        if self.records.insert(&account_id, &y).is_some() {
-                            self.contract_state.score_count += 1;
-                            success = true;
-                            log!("Score stored successfully to NEAR blockchain");
-                        }
-                    } else {
-                        env::panic_str(
-                            "ERR_EXCEEDED_HUNDRED_SCORES_UPPERBOUND_OR_LATEST_SCORE_IS_TOO_RECENT",
-                        )
-                    }
-                }
+    // retract the caller's latest score, e.g. one published by mistake, within a short grace period
+    // a user whose only score got retracted is removed altogether
+    pub fn retract_latest_score(&mut self) {
+        let account_id = String::from(env::predecessor_account_id());
+        let mut scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let latest = scores
+            .pop()
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        if env::block_timestamp().saturating_sub(latest.timestamp) >= GRACE_NS {
+            env::panic_str("ERR_GRACE_EXPIRED")
+        }
+        // write the shortened vector back first, so that remove_user doesn't count the retracted score again
+        self.records.insert(&account_id, &scores);
+        if scores.is_empty() {
+            self.remove_user(&account_id);
+        } else {
+            self.update_score_sum(&account_id, 0, latest.score);
+        }
+        self.contract_state.score_count = checked_decrease(self.contract_state.score_count, 1);
+        self.log_event("score_retracted", json!({ "account_id": account_id, "id": latest.id }));
+    }
+
+    // store a new score to blockchain on behalf of another account
+    // only the contract owner and the registered oracles can submit scores for others,
+    // and they pay for the score's storage the same way users do
+    #[payable]
+    pub fn store_score_for(&mut self, account_id: String, score: u16, description: String) -> ScoreOutcome {
+        let oracle = env::predecessor_account_id();
+        if !self.owners.contains(&oracle) && !self.oracles.contains(&oracle) {
+            env::panic_str("ERR_NOT_AUTHORIZED_ORACLE")
+        }
+        self.paid_write(env::attached_deposit(), |contract| {
+            let outcome = contract.internal_store_score(account_id.clone(), score, description);
+
+            // keep track of the distinct accounts each oracle has scored
+            let mut subjects = contract.oracle_subjects.get(&oracle).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::OracleSubjectsInner {
+                    oracle_hash: env::sha256(oracle.as_str().as_bytes()),
+                })
+            });
+            subjects.insert(&account_id);
+            contract.oracle_subjects.insert(&oracle, &subjects);
+            outcome
+        })
+    }
+
+    // store a new score and mint an NFT attesting to it on the linked NFT contract
+    // the attached deposit is forwarded to nft_mint to pay for the token's storage,
+    // so this contract must be an authorized minter of the NFT contract
+    // the score's own storage is paid like any other write's, with none of the attached deposit left to pay it:
+    // once a minimum deposit is set, callers need a registered storage balance
+    #[payable]
+    pub fn store_score_and_mint(&mut self, score: u16, description: String, token_id: String) -> PromiseOrValue<bool> {
+        let nft_contract = self
+            .config
+            .nft_contract
+            .clone()
+            .unwrap_or_else(|| env::panic_str("ERR_NFT_CONTRACT_NOT_SET"));
+        let account_id = String::from(env::predecessor_account_id());
+        self.paid_write(0, |contract| contract.internal_store_score(account_id.clone(), score, description));
+
+        let metadata = json!({
+            "title": format!("Credit score of {}", account_id),
+            "description": "NEARoracle credit score attestation",
+            "media": "",
+            "issued_at": env::block_timestamp() / 1_000_000,
+        });
+        let args = json!({
+            "token_id": token_id,
+            "metadata": metadata,
+            "receiver_id": account_id,
+            "score": score,
+        });
+        Promise::new(nft_contract)
+            .function_call(
+                "nft_mint".to_string(),
+                args.to_string().into_bytes(),
+                env::attached_deposit(),
+                GAS_FOR_NFT_MINT,
+            )
+            .then(ext_self::on_score_nft_minted(
+                account_id,
+                token_id,
+                env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_MINT,
+            ))
+            .into()
+    }
+
+    // resolve the mint initiated by store_score_and_mint
+    // the score is already committed, so a failed mint is only surfaced in the logs
+    #[private]
+    pub fn on_score_nft_minted(&mut self, account_id: String, token_id: String) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                log!("Minted {} for {}", token_id, account_id);
+                true
+            }
+            _ => {
+                log!("ERR_NFT_MINT_FAILED: the score of {} was stored but {} wasn't minted", account_id, token_id);
+                false
             }
         }
-        // return an outcome struct describing whether the
-        // operation of storing a score to blockchain was successful
-        ScoreOutcome {
-            gas_used: env::used_gas(),
-            score_owner: account_id,
-            successful_operation: success,
+    }
+
+    // query a user's latest score together with the number of tokens they hold on an NFT contract
+    // this makes a cross-contract call, so it must be called as a transaction rather than a view
+    pub fn user_status(&self, account_id: String, nft_contract: AccountId) -> Promise {
+        let score = self.query_latest_score(account_id.clone());
+        Promise::new(nft_contract)
+            .function_call(
+                "nft_supply_for_owner".to_string(),
+                json!({ "account_id": account_id }).to_string().into_bytes(),
+                0,
+                GAS_FOR_NFT_SUPPLY,
+            )
+            .then(ext_self::on_user_status(
+                score,
+                env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_STATUS,
+            ))
+    }
+
+    // merge the NFT contract's answer into the status queried by user_status
+    #[private]
+    pub fn on_user_status(&self, score: u16) -> UserStatus {
+        match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                let nft_count: U128 = serde_json::from_slice(&value)
+                    .unwrap_or_else(|_| env::panic_str("ERR_INVALID_NFT_SUPPLY"));
+                UserStatus { score, nft_count }
+            }
+            _ => env::panic_str("ERR_NFT_QUERY_FAILED"),
         }
     }
 
-    // query all score history for a specified user
-    pub fn query_score_history(&self, account_id: String) -> MyScoreHistory {
+    // query the score history for a specified user, oldest first unless reverse is true
+    // from_index and limit page through the history, from_index counting from the newest end when reversed
+    // accounts without any score, whether unknown or emptied, get an empty history
+    pub fn query_score_history(
+        &self,
+        account_id: String,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+        reverse: Option<bool>,
+    ) -> MyScoreHistory {
         if let Some(a) = self.records.get(&account_id) {
-            
+            let len = a.len();
+            let start = from_index.unwrap_or(0).min(len);
+            let end = start.saturating_add(limit.unwrap_or(len)).min(len);
+            let reverse = reverse.unwrap_or(false);
+
             let mut score_history = vec![];
-            for i in a.iter() {
+            for k in start..end {
+                let i = a.get(if reverse { len - 1 - k } else { k }).unwrap();
                 let s = Score {
+                    id: i.id,
                     score: i.score,
                     timestamp: i.timestamp,
                     description: String::from_utf8(i.description).unwrap(), //decrypt message
                 };
                 score_history.push(s);
             };
-            return MyScoreHistory {
+            MyScoreHistory {
                 scores: score_history,
-            };
+            }
         } else {
-            // implement logic in case the above Option<T> returns a NoneType
-            env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY")
+            // an unknown account simply has no history, just like an emptied one
+            MyScoreHistory { scores: vec![] }
         }
     }
 
-    // -----------------------------------------------------//
-    //              State-related implementations           //
-    // -----------------------------------------------------//
+    // query a user's full score history as base64 of the borsh-serialized Vec<User>,
+    // far more compact than JSON for indexers sharing the borsh schema
+    pub fn query_score_history_raw(&self, account_id: String) -> Base64VecU8 {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        Base64VecU8(scores.to_vec().try_to_vec().unwrap())
+    }
 
-    // gasless query of the state of the contract at a point in time
-    pub fn read_state(&self) -> ContractState {
-        ContractState {
-            owner: String::from(env::current_account_id()),
-            timestamp: env::block_timestamp(),
-            size_now: env::storage_usage(),
-            user_count: self.contract_state.user_count,
-            score_count: self.contract_state.score_count,
-        }
+    // query a user's score history as (score, timestamp) pairs, oldest first, leaving descriptions out
+    pub fn query_scores_only(&self, account_id: String) -> Vec<(u16, u64)> {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        scores.iter().map(|u| (u.score, u.timestamp)).collect()
     }
 
-    // check whether a user has a score record - for testing only (?)
-    pub fn user_exist(&self, account_id: String) -> bool {
-        return self.records.get(&account_id).is_some();
+    // query the scores of a user written by a given issuer, oldest first
+    pub fn query_scores_by_issuer(&self, account_id: String, issuer: AccountId) -> Vec<User> {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        scores.iter().filter(|u| u.issuer == issuer).collect()
     }
 
-    // return the length of the user's score history
-    pub fn maxout_check(&self, account_id: String) -> u64 {
-        if let Some(i) = self.records.get(&account_id) {
-            let count = i.len();
-            return count;
-        } else {
-            let count: u64 = 0;
-            return count;
+    // query a single entry of a user's score history by its position, oldest first
+    pub fn query_score_at(&self, account_id: String, index: u64) -> User {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        scores
+            .get(index)
+            .unwrap_or_else(|| env::panic_str("ERR_INDEX_OUT_OF_BOUNDS"))
+    }
+
+    // the score a user held at a given moment: their most recent score stored at or before timestamp
+    pub fn score_at_timestamp(&self, account_id: String, timestamp: u64) -> Option<User> {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        // scores are appended in chronological order, so stop at the first one past the timestamp
+        let mut held = None;
+        for u in scores.iter() {
+            if u.timestamp > timestamp {
+                break;
+            }
+            held = Some(u);
         }
+        held
     }
-}
 
-/*
- * the rest of this file sets up unit tests
- * execute them running the command:
- * cargo test --package near_oracle -- --nocapture
- * Note: 'near_oracle' comes from Cargo.toml's 'name' key
- */
+    // the scores a user stored between start and end, both included
+    pub fn scores_between(&self, account_id: String, start: u64, end: u64) -> Vec<User> {
+        if start > end {
+            env::panic_str("ERR_INVALID_RANGE")
+        }
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        // scores are appended in chronological order, so stop at the first one past the range
+        scores
+            .iter()
+            .skip_while(|u| u.timestamp < start)
+            .take_while(|u| u.timestamp <= end)
+            .collect()
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env, AccountId, VMContext};
-    use std::convert::TryInto;
+    // dry run of store_score for a given account: the same checks, reported instead of panicking
+    pub fn validate_score_input(&self, account_id: String, score: u16, description: String) -> ValidationResult {
+        let reason = self.score_input_error(&account_id, score, &description).map(|(_, reason)| reason);
+        ValidationResult { valid: reason.is_none(), reason }
+    }
 
-    // define 3 fake users
-    fn doomslug() -> AccountId {
-        "doomslug.testnet".to_string().try_into().unwrap()
+    // query the latest score of a user, e.g. from another contract gating actions on it
+    pub fn query_latest_score(&self, account_id: String) -> u16 {
+        self.latest_score(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"))
     }
 
-    fn spensa() -> AccountId {
-        "spensa.testnet".to_string().try_into().unwrap()
+    // whether the latest score of a user is at most max_age_secs old, unknown users have no fresh score
+    pub fn is_score_fresh(&self, account_id: String, max_age_secs: u64) -> bool {
+        match self.latest_entry(&account_id) {
+            Some(latest) => {
+                env::block_timestamp().saturating_sub(latest.timestamp)
+                    <= max_age_secs.saturating_mul(u64::pow(10, 9))
+            }
+            None => false,
+        }
     }
 
-    fn rainbow() -> AccountId {
-        "rainbow.testnet".to_string().try_into().unwrap()
+    // query the latest score of a user mapped from [MIN_SCORE, MAX_SCORE] onto [0, 100]
+    pub fn query_latest_score_normalized(&self, account_id: String) -> u8 {
+        normalize_score(self.query_latest_score(account_id))
     }
 
-    // part of writing unit tests is setting up a mock context
-    // provide a `predecessor` here, it'll modify the default context
-    fn get_context(is_view: bool, predecessor: AccountId ) -> VMContext {
-        VMContextBuilder::new()
-            // set 'spensa.testnet' to be the contract owner
-            .current_account_id("spensa.testnet".to_string().try_into().unwrap())
-            .predecessor_account_id(predecessor)
-            .block_timestamp(0u64)
-            .storage_usage(0u64)
-            .is_view(is_view)
-            .build()
+    // return the latest score of a user iff it's at least min_score and no older than max_age_seconds
+    pub fn qualified_score(&self, account_id: String, min_score: u16, max_age_seconds: u64) -> Option<u16> {
+        let latest = self
+            .latest_entry(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let age = env::block_timestamp().saturating_sub(latest.timestamp);
+        if latest.score >= min_score && age <= max_age_seconds.saturating_mul(u64::pow(10, 9)) {
+            Some(latest.score)
+        } else {
+            None
+        }
     }
 
-    // implement two methods to return the length and to index the vector in the MyScoreHistory struct
-    impl MyScoreHistory {
-        fn len(&self) -> usize {
-            self.scores.len()
+    // query a user's score history as CSV, one "timestamp,score" line per score after a header line
+    pub fn query_scores_csv(&self, account_id: String) -> String {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let mut csv = String::from("timestamp,score\n");
+        for u in scores.iter() {
+            csv.push_str(&format!("{},{}\n", u.timestamp, u.score));
         }
+        csv
     }
 
-    #[test]
-    fn initialize_stats() {
-        let context = get_context(true, spensa());
-        testing_env!(context);
-        let contract = Contract::new(spensa());
+    // export a user's full score history as a JSON string, for off-chain archival
+    // descriptions are rendered as the hex encoding of their sha256 digest
+    pub fn export_user_json(&self, account_id: String) -> String {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let entries: Vec<serde_json::Value> = scores
+            .iter()
+            .map(|u| {
+                json!({
+                    "id": u.id,
+                    "score": u.score,
+                    "timestamp": u.timestamp,
+                    "description": hex::encode(env::sha256(&u.description)),
+                })
+            })
+            .collect();
+        serde_json::to_string(&json!({ "account_id": account_id, "scores": entries })).unwrap()
+    }
 
-        // ensure that 'Contract' parameters are empty or null at initialization
-        assert_eq!(
-            0, contract.contract_state.user_count,
-            "ERR: User count should be 0 at initialization"
-        );
-        assert_eq!(
-            0, contract.contract_state.score_count,
-            "ERR: Score count should be 0 at initialization"
-        );
-        assert_eq!(
-            contract.owner_id,
-            spensa(),
-            "ERR: owner ids should coincide"
-        );
+    // count the distinct descriptions among a user's scores, flagging reused canned messages
+    pub fn distinct_descriptions_for(&self, account_id: String) -> u64 {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let digests: HashSet<Vec<u8>> = scores.iter().map(|u| env::sha256(&u.description)).collect();
+        digests.len() as u64
     }
 
-    #[test]
-    fn store_multiple_scores() {
-        let context = get_context(false, doomslug());
-        testing_env!(context);
-        let mut contract = Contract::new(spensa());
+    // check a description against the one stored with a user's score at a given index, comparing sha256 digests
+    pub fn verify_description(&self, account_id: String, index: u64, description: String) -> bool {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let entry = scores
+            .get(index)
+            .unwrap_or_else(|| env::panic_str("ERR_INDEX_OUT_OF_BOUNDS"));
+        env::sha256(description.as_bytes()) == env::sha256(&entry.description)
+    }
 
-        // check initialization values are correct
-        assert_eq!(0, contract.contract_state.user_count);
-        assert_eq!(0, contract.contract_state.score_count);
-        assert_eq!(
-            doomslug().to_string(),
-            String::from(env::predecessor_account_id())
-        );
+    // query a user's average score from the cached running sum, without reading the history
+    pub fn cached_average(&self, account_id: String) -> u16 {
+        let meta = self
+            .user_meta
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let count = self.records.get(&account_id).map(|scores| scores.len()).unwrap_or(0);
+        if count == 0 {
+            env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY")
+        }
+        (meta.sum / count) as u16
+    }
 
- 
-        /*
-        * we will now simulate the interaction of three distinct users with the contract:
-        * user 1 will store 1 score to chain (username: doomslug)
-        * user 2 will store 2 scores to chain (username: spensa)
-        * user 3 will store 3 scores to chain (username: rainbow)
-        */
+    // query the timestamps of a user's oldest and newest scores
+    pub fn user_time_span(&self, account_id: String) -> (u64, u64) {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        if scores.is_empty() {
+            env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY")
+        }
+        // histories are append-only, so they're ordered by timestamp
+        let first = scores.get(0).unwrap().timestamp;
+        let last = scores.get(scores.len() - 1).unwrap().timestamp;
+        (first, last)
+    }
 
-        // -------------- //
-        //      user 1    //
-        // -------------- //
-        // store first score
-        let msg1 = "Sorry, your score is only 300 points".to_string();
-        let out1 = contract.store_score(300, msg1);
-        assert!(out1.successful_operation);
-        assert_eq!(String::from(env::predecessor_account_id()), out1.score_owner);
+    // the latest scores stored across all users, newest first, for a live activity feed
+    pub fn recent_activity(&self, limit: u64) -> Vec<RecentScore> {
+        let len = self.recent_scores.len();
+        (0..limit.min(len))
+            .map(|k| {
+                let slot = (self.recent_writes - 1 - k) % MAX_RECENT_SCORES;
+                let (account_id, score, timestamp) = self.recent_scores.get(slot).unwrap();
+                RecentScore { account_id, score, timestamp }
+            })
+            .collect()
+    }
+
+    // query the timestamp at which a user stored their very first score
+    pub fn user_since(&self, account_id: String) -> u64 {
+        match self.user_meta.get(&account_id) {
+            Some(meta) => meta.since,
+            None => env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"),
+        }
+    }
+
+    // count how many scores a user stored in a given calendar month (UTC)
+    pub fn scores_in_month(&self, account_id: String, year: u64, month: u8) -> u64 {
+        if !(1..=12).contains(&month) {
+            env::panic_str("ERR_INVALID_MONTH")
+        }
+        if year < 1970 {
+            env::panic_str("ERR_INVALID_YEAR")
+        }
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+
+        // the window spans from the first day of the month up to the first day of the next one
+        let start = month_start_ns(year, month);
+        let end = if month == 12 { month_start_ns(year + 1, 1) } else { month_start_ns(year, month + 1) };
+        scores.iter().filter(|u| u.timestamp >= start && u.timestamp < end).count() as u64
+    }
+
+    // signed difference between a user's latest score and the one before it
+    // only the last two elements of the vector are read, to keep the call cheap
+    pub fn query_score_trend(&self, account_id: String) -> i32 {
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let len = scores.len();
+        if len == 0 {
+            env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY")
+        }
+        if len == 1 {
+            return 0;
+        }
+        let latest = scores.get(len - 1).unwrap().score as i32;
+        let previous = scores.get(len - 2).unwrap().score as i32;
+        latest - previous
+    }
+
+    // query a user's average score with every score's weight halving each half_life_days of age
+    pub fn query_weighted_score(&self, account_id: String, half_life_days: u64) -> u16 {
+        if half_life_days == 0 {
+            env::panic_str("ERR_INVALID_HALF_LIFE")
+        }
+        let scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        if scores.is_empty() {
+            env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY")
+        }
+        let half_life = half_life_days.saturating_mul(NS_PER_DAY);
+        let now = env::block_timestamp();
+        let mut weighted_sum: u128 = 0;
+        let mut total_weight: u128 = 0;
+        for u in scores.iter() {
+            let weight = decay_weight(now.saturating_sub(u.timestamp), half_life);
+            weighted_sum += weight * u.score as u128;
+            total_weight += weight;
+        }
+        // every score has decayed away, fall back to the most recent one
+        if total_weight == 0 {
+            return scores.get(scores.len() - 1).unwrap().score;
+        }
+        ((weighted_sum + total_weight / 2) / total_weight) as u16
+    }
+
+    // percentile rank (0-100) of a user's latest score among all users' latest scores,
+    // users with the very same score counting as half below
+    pub fn score_percentile(&self, account_id: String) -> u8 {
+        let score = self.query_latest_score(account_id);
+        let mut below = 0u64;
+        let mut equal = 0u64;
+        let mut total = 0u64;
+        for latest in self.accounts.iter().filter_map(|a| self.latest_score(&a)) {
+            total += 1;
+            if latest < score {
+                below += 1;
+            } else if latest == score {
+                equal += 1;
+            }
+        }
+        ((2 * below + equal) * 100 / (2 * total)) as u8
+    }
+
+    // query the median of a user's score history
+    pub fn query_median_score(&self, account_id: String) -> u16 {
+        median(&self.score_values(&account_id))
+    }
+
+    // integer percentage of a user's scores that lie strictly above their own median
+    pub fn share_above_personal_median(&self, account_id: String) -> u8 {
+        let scores = self.score_values(&account_id);
+        let personal_median = median(&scores);
+        let above = scores.iter().filter(|s| **s > personal_median).count();
+        (above * 100 / scores.len()) as u8
+    }
+
+    // histogram of every user's latest score, binned into equal-width ranges between MIN_SCORE and MAX_SCORE
+    pub fn score_distribution(&self, buckets: u16) -> Vec<u64> {
+        if !(1..=100).contains(&buckets) {
+            env::panic_str("ERR_INVALID_BUCKET_COUNT")
+        }
+        let mut counts = vec![0u64; buckets as usize];
+        let range = (MAX_SCORE - MIN_SCORE) as u64;
+        for account_id in self.accounts.iter() {
+            if let Some(score) = self.latest_score(&account_id) {
+                // scores outside of the range fall into the first or last bucket
+                let offset = score.clamp(MIN_SCORE, MAX_SCORE) - MIN_SCORE;
+                let bucket = (offset as u64 * buckets as u64 / range).min(buckets as u64 - 1);
+                counts[bucket as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    // the users whose latest score improved the most over their earliest one, best first
+    // only users with a positive delta are returned, at most limit (capped at 100) of them
+    pub fn most_improved(&self, limit: u64) -> Vec<ScoredAccount> {
+        let mut improved: Vec<ScoredAccount> = vec![];
+        for account_id in self.accounts.iter() {
+            let scores = self.score_values(&account_id);
+            let latest = scores[scores.len() - 1];
+            let delta = latest as i32 - scores[0] as i32;
+            if delta > 0 {
+                improved.push(ScoredAccount { account_id, score: latest, delta });
+            }
+        }
+        // break ties on the account id, so that the ranking doesn't depend on storage order
+        improved.sort_by(|a, b| b.delta.cmp(&a.delta).then_with(|| a.account_id.cmp(&b.account_id)));
+        improved.truncate(limit.min(MAX_RANKING_SIZE) as usize);
+        improved
+    }
+
+    // the users with the highest latest score, best first, at most limit (capped at 100) of them
+    // delta is the move of the latest score over the previous one
+    // every user is loaded and sorted in memory (O(n log n)), so this suits modest user counts only
+    pub fn leaderboard(&self, limit: u64) -> Vec<ScoredAccount> {
+        let mut ranking: Vec<ScoredAccount> = vec![];
+        for account_id in self.accounts.iter() {
+            let scores = self.score_values(&account_id);
+            let latest = scores[scores.len() - 1];
+            let previous = if scores.len() > 1 { scores[scores.len() - 2] } else { latest };
+            ranking.push(ScoredAccount { account_id, score: latest, delta: latest as i32 - previous as i32 });
+        }
+        // break ties on the account id, so that the ranking doesn't depend on storage order
+        ranking.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.account_id.cmp(&b.account_id)));
+        ranking.truncate(limit.min(MAX_RANKING_SIZE) as usize);
+        ranking
+    }
+
+    // compare a user's latest score against the average latest score of a peer group
+    // a positive output means the user is above their peers, a negative one below
+    pub fn score_vs_group(&self, account_id: String, group: Vec<String>) -> i32 {
+        if group.len() > MAX_GROUP_SIZE {
+            env::panic_str("ERR_GROUP_TOO_LARGE")
+        }
+        let latest = self
+            .latest_score(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+
+        // group members without a score history are left out of the average
+        let peers: Vec<u16> = group.iter().filter_map(|a| self.latest_score(a)).collect();
+        if peers.is_empty() {
+            env::panic_str("ERR_GROUP_HAS_NO_SCORE_HISTORY")
+        }
+        let sum: i64 = peers.iter().map(|s| *s as i64).sum();
+        let average = sum / peers.len() as i64;
+        latest as i32 - average as i32
+    }
+
+    // -----------------------------------------------------//
+    //              Owner-only implementations              //
+    // -----------------------------------------------------//
+
+    // link (or unlink, passing null) the NFT contract minting credit-score attestations
+    pub fn set_nft_contract(&mut self, nft_contract: Option<AccountId>) {
+        self.assert_owner();
+        let target = nft_contract.as_ref().map(|a| a.to_string()).unwrap_or_default();
+        self.config.nft_contract = nft_contract;
+        self.log_admin_action("set_nft_contract", &target);
+    }
+
+    // set (or clear, passing null) the minimum score that triggers an automatic mint
+    pub fn set_auto_mint_threshold(&mut self, threshold: Option<u16>) {
+        self.assert_owner();
+        self.config.auto_mint_threshold = threshold;
+        self.log_admin_action("set_auto_mint_threshold", &threshold.map(|t| t.to_string()).unwrap_or_default());
+    }
+
+    // override the maximum number of scores a given user may store
+    pub fn set_user_cap(&mut self, account_id: String, cap: u16) {
+        self.assert_owner();
+        self.user_caps.insert(&account_id, &cap);
+        self.log_admin_action("set_user_cap", &account_id);
+    }
+
+    // override the score caps of many users at once, e.g. when onboarding VIP accounts
+    pub fn set_user_caps_batch(&mut self, caps: Vec<(String, u16)>) {
+        self.assert_owner();
+        if caps.len() > MAX_BATCH_SIZE {
+            env::panic_str("ERR_BATCH_TOO_LARGE")
+        }
+        for (account_id, cap) in caps.iter() {
+            self.user_caps.insert(account_id, cap);
+        }
+        let targets: Vec<&str> = caps.iter().map(|(a, _)| a.as_str()).collect();
+        self.log_admin_action("set_user_caps_batch", &targets.join(","));
+    }
+
+    // register an ed25519 public key whose signed attestations users may submit
+    pub fn add_attestation_key(&mut self, pubkey: Base64VecU8) {
+        self.assert_owner();
+        self.attestation_keys.insert(&pubkey.0);
+        self.log_admin_action("add_attestation_key", &hex::encode(&pubkey.0));
+    }
+
+    // revoke an ed25519 public key, its attestations can't be submitted anymore
+    pub fn remove_attestation_key(&mut self, pubkey: Base64VecU8) {
+        self.assert_owner();
+        self.attestation_keys.remove(&pubkey.0);
+        self.log_admin_action("remove_attestation_key", &hex::encode(&pubkey.0));
+    }
+
+    // let another account administer the contract alongside the current owners
+    pub fn add_owner(&mut self, owner: AccountId) {
+        self.assert_owner();
+        self.owners.insert(&owner);
+        self.log_admin_action("add_owner", owner.as_str());
+    }
+
+    // revoke an owner, the contract always keeps at least one.
+    // Revoking the primary owner hands that role to the caller, so the
+    // primary owner can't revoke itself
+    pub fn remove_owner(&mut self, owner: AccountId) {
+        self.assert_owner();
+        if self.owners.len() == 1 && self.owners.contains(&owner) {
+            env::panic_str("ERR_CANNOT_REMOVE_LAST_OWNER")
+        }
+        if owner == self.primary_owner {
+            let caller = env::predecessor_account_id();
+            if caller == owner {
+                env::panic_str("ERR_CANNOT_REMOVE_PRIMARY_OWNER")
+            }
+            self.primary_owner = caller;
+        }
+        self.owners.remove(&owner);
+        self.log_admin_action("remove_owner", owner.as_str());
+    }
+
+    // authorize an oracle to submit scores on behalf of other accounts
+    pub fn add_oracle(&mut self, oracle: AccountId) {
+        self.assert_owner();
+        self.oracles.insert(&oracle);
+        self.log_admin_action("add_oracle", oracle.as_str());
+    }
+
+    // revoke an oracle's permission to submit scores on behalf of other accounts
+    pub fn remove_oracle(&mut self, oracle: AccountId) {
+        self.assert_owner();
+        self.oracles.remove(&oracle);
+        self.log_admin_action("remove_oracle", oracle.as_str());
+    }
+
+    // delete a user together with their whole score history
+    pub fn delete_user(&mut self, account_id: String) {
+        self.assert_owner();
+        self.remove_user(&account_id);
+        self.log_admin_action("delete_user", &account_id);
+    }
+
+    // replace a user's whole score history, e.g. after the oracle's model got recomputed
+    // every new score gets a fresh id and the current block timestamp
+    pub fn overwrite_history(&mut self, account_id: String, scores: Vec<u16>, descriptions: Vec<String>) {
+        self.assert_owner();
+        if scores.len() != descriptions.len() {
+            env::panic_str("ERR_LENGTH_MISMATCH")
+        }
+        if scores.is_empty() {
+            env::panic_str("ERR_EMPTY_HISTORY")
+        }
+        if scores.len() > self.cap_for(&account_id) as usize {
+            env::panic_str("ERR_HISTORY_EXCEEDS_CAP")
+        }
+        if scores.iter().any(|score| !(MIN_SCORE..=MAX_SCORE).contains(score)) {
+            env::panic_str("ERR_SCORE_OUT_OF_RANGE")
+        }
+        if descriptions.iter().any(|d| d.as_bytes().len() > self.config.max_desc_len as usize) {
+            env::panic_str("ERR_DESCRIPTION_TOO_LONG")
+        }
+
+        let timestamp = env::block_timestamp();
+        let sum: u64 = scores.iter().map(|score| *score as u64).sum();
+        let mut history = match self.records.get(&account_id) {
+            Some(mut history) => {
+                self.contract_state.score_count = checked_decrease(self.contract_state.score_count, history.len());
+                history.clear();
+                // the user keeps its registration date, only the cached sum follows the new scores
+                let since = self.user_meta.get(&account_id).map(|meta| meta.since).unwrap_or(timestamp);
+                self.user_meta.insert(&account_id, &UserMeta { since, sum });
+                history
+            }
+            // a brand new user gets registered the same way store_score would
+            None => {
+                self.user_meta.insert(&account_id, &UserMeta { since: timestamp, sum });
+                self.accounts.insert(&account_id);
+                self.contract_state.user_count = checked_increase(self.contract_state.user_count, 1);
+                Vector::new(StorageKey::Accounts { account_hash: env::sha256(account_id.as_bytes()) })
+            }
+        };
+        for (score, description) in scores.iter().zip(descriptions.iter()) {
+            history.push(&User {
+                id: self.contract_state.next_score_id,
+                score: *score,
+                timestamp,
+                description: description.as_bytes().to_vec(),
+                issuer: env::predecessor_account_id(),
+            });
+            self.contract_state.next_score_id = checked_increase(self.contract_state.next_score_id, 1);
+        }
+        self.records.insert(&account_id, &history);
+        self.contract_state.score_count = checked_increase(self.contract_state.score_count, history.len());
+        self.log_admin_action("overwrite_history", &account_id);
+    }
+
+    // add many historical scores to a user's history in one write, e.g. when migrating an account
+    // the entries are merged into the stored scores by timestamp, so that older history can be backfilled too
+    // the cooldown doesn't apply, but the cap, the score range and the description length do
+    pub fn backfill_scores(&mut self, account_id: String, entries: Vec<(u16, u64, String)>) {
+        self.assert_owner();
+        if entries.is_empty() {
+            return;
+        }
+        if entries.iter().any(|(score, _, _)| !(MIN_SCORE..=MAX_SCORE).contains(score)) {
+            env::panic_str("ERR_SCORE_OUT_OF_RANGE")
+        }
+        if entries.iter().any(|(_, _, d)| d.as_bytes().len() > self.config.max_desc_len as usize) {
+            env::panic_str("ERR_DESCRIPTION_TOO_LONG")
+        }
+        // a score dated in the future would hold off the user's next score until then
+        if entries.iter().any(|(_, timestamp, _)| *timestamp > env::block_timestamp()) {
+            env::panic_str("ERR_FUTURE_TIMESTAMP")
+        }
+        // entries must follow each other in time
+        if entries.windows(2).any(|pair| pair[1].1 <= pair[0].1) {
+            env::panic_str("ERR_NONMONOTONIC_TIMESTAMPS")
+        }
+
+        let oldest = entries[0].1;
+        let added: u64 = entries.iter().map(|(score, _, _)| *score as u64).sum();
+        let (mut history, meta) = match self.records.get(&account_id) {
+            // the user may now have shown up earlier than it was first seen
+            Some(history) => {
+                let meta = self.user_meta.get(&account_id).unwrap_or(UserMeta { since: oldest, sum: 0 });
+                let meta = UserMeta { since: meta.since.min(oldest), sum: checked_increase(meta.sum, added) };
+                (history, meta)
+            }
+            // a brand new user gets registered as of its oldest backfilled score
+            None => {
+                self.accounts.insert(&account_id);
+                self.contract_state.user_count = checked_increase(self.contract_state.user_count, 1);
+                let history = Vector::new(StorageKey::Accounts { account_hash: env::sha256(account_id.as_bytes()) });
+                (history, UserMeta { since: oldest, sum: added })
+            }
+        };
+        if history.len() + entries.len() as u64 > self.cap_for(&account_id) as u64 {
+            env::panic_str("ERR_HISTORY_EXCEEDS_CAP")
+        }
+
+        // take off the stored scores newer than the oldest entry, the older ones stay where they are,
+        // and push them back interleaved with the entries, so that the history stays in chronological order
+        let mut newer = vec![];
+        while let Some(latest) = history.pop() {
+            if latest.timestamp <= oldest {
+                history.push(&latest);
+                break;
+            }
+            newer.push(latest);
+        }
+        let mut newer = newer.into_iter().rev().peekable();
+        for (score, timestamp, description) in entries.iter() {
+            while let Some(stored) = newer.next_if(|u| u.timestamp <= *timestamp) {
+                history.push(&stored);
+            }
+            history.push(&User {
+                id: self.contract_state.next_score_id,
+                score: *score,
+                timestamp: *timestamp,
+                description: description.as_bytes().to_vec(),
+                issuer: env::predecessor_account_id(),
+            });
+            self.contract_state.next_score_id = checked_increase(self.contract_state.next_score_id, 1);
+        }
+        for stored in newer {
+            history.push(&stored);
+        }
+        self.records.insert(&account_id, &history);
+        self.user_meta.insert(&account_id, &meta);
+        self.contract_state.score_count = checked_increase(self.contract_state.score_count, entries.len() as u64);
+        self.log_admin_action("backfill_scores", &account_id);
+    }
+
+    // correct the description of a single stored score, its score, id and timestamp are kept
+    pub fn amend_description(&mut self, account_id: String, index: u64, new_description: String) {
+        self.assert_owner();
+        if new_description.as_bytes().len() > self.config.max_desc_len as usize {
+            env::panic_str("ERR_DESCRIPTION_TOO_LONG")
+        }
+        let mut scores = self
+            .records
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let mut entry = scores
+            .get(index)
+            .unwrap_or_else(|| env::panic_str("ERR_INDEX_OUT_OF_BOUNDS"));
+        entry.description = new_description.as_bytes().to_vec();
+        scores.replace(index, &entry);
+        self.log_admin_action("amend_description", &account_id);
+    }
+
+    // wipe all score histories, along with the per-account data and the activity feed, and zero the counters,
+    // e.g. between testnet redeployments
+    // score ids keep increasing, so that ids handed out before the reset are never reused
+    pub fn reset(&mut self) {
+        self.assert_owner();
+        if !self.config.allow_reset {
+            env::panic_str("ERR_RESET_DISABLED")
+        }
+        let accounts = self.accounts.to_vec();
+        for account_id in accounts.iter() {
+            // the oracles that scored the account are among the issuers of its scores
+            let issuers: HashSet<AccountId> = self
+                .records
+                .get(account_id)
+                .map(|scores| scores.iter().map(|u| u.issuer).collect())
+                .unwrap_or_default();
+            for oracle in issuers.iter() {
+                if let Some(mut subjects) = self.oracle_subjects.get(oracle) {
+                    subjects.remove(account_id);
+                    if subjects.is_empty() {
+                        self.oracle_subjects.remove(oracle);
+                    } else {
+                        self.oracle_subjects.insert(oracle, &subjects);
+                    }
+                }
+            }
+            self.nonces.remove(account_id);
+            self.attested_at.remove(account_id);
+            self.remove_user(account_id);
+        }
+        self.recent_scores.clear();
+        self.recent_writes = 0;
+        self.contract_state.user_count = 0;
+        self.contract_state.score_count = 0;
+        self.log_event(
+            "contract_reset",
+            json!({
+                "owner": env::predecessor_account_id().to_string(),
+                "users_removed": accounts.len(),
+            }),
+        );
+    }
+
+    // -----------------------------------------------------//
+    //             Storage-related implementations          //
+    // -----------------------------------------------------//
+
+    // top up the storage balance of an account, the caller by default (NEP-145)
+    // with registration_only, an account that is already registered gets the whole deposit back,
+    // and a new one is registered with the minimum balance, the estimated cost of its next score
+    // (see estimate_store_cost), getting the rest back
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let deposit = env::attached_deposit();
+        let balance = match self.storage_deposits.get(&account_id) {
+            Some(balance) if registration_only.unwrap_or(false) => {
+                if deposit > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(deposit);
+                }
+                balance
+            }
+            Some(balance) => balance + deposit,
+            None if registration_only.unwrap_or(false) => {
+                let minimum = self.estimate_store_cost(account_id.to_string()).deposit.0;
+                if deposit < minimum {
+                    env::panic_str("ERR_DEPOSIT_BELOW_MINIMUM")
+                }
+                if deposit > minimum {
+                    Promise::new(env::predecessor_account_id()).transfer(deposit - minimum);
+                }
+                minimum
+            }
+            None => {
+                if deposit == 0 {
+                    env::panic_str("ERR_ZERO_DEPOSIT")
+                }
+                deposit
+            }
+        };
+        self.storage_deposits.insert(&account_id, &balance);
+        StorageBalance { total: U128(balance), available: U128(balance) }
+    }
+
+    // withdraw part of the caller's storage balance, all of it by default (NEP-145)
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        if env::attached_deposit() != 1 {
+            env::panic_str("ERR_REQUIRES_ONE_YOCTO")
+        }
+        let account_id = env::predecessor_account_id();
+        let balance = self
+            .storage_deposits
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_NOT_REGISTERED"));
+        let amount = amount.map(|a| a.0).unwrap_or(balance);
+        if amount > balance {
+            env::panic_str("ERR_AMOUNT_EXCEEDS_BALANCE")
+        }
+        self.storage_deposits.insert(&account_id, &(balance - amount));
+        // the attached yoctoNEAR goes back along with the withdrawal
+        Promise::new(account_id).transfer(amount + 1);
+        StorageBalance { total: U128(balance - amount), available: U128(balance - amount) }
+    }
+
+    // the storage balance of an account, None if it never registered (NEP-145)
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_deposits
+            .get(&account_id)
+            .map(|balance| StorageBalance { total: U128(balance), available: U128(balance) })
+    }
+
+    // -----------------------------------------------------//
+    //              State-related implementations           //
+    // -----------------------------------------------------//
+
+    // gasless query of the state of the contract at a point in time
+    pub fn read_state(&self) -> ContractState {
+        ContractState {
+            version: VERSION.to_string(),
+            owner: self.get_owner().to_string(),
+            timestamp: env::block_timestamp(),
+            size_now: env::storage_usage(),
+            user_count: self.contract_state.user_count,
+            score_count: self.contract_state.score_count,
+            scores_per_user: if self.contract_state.user_count == 0 {
+                0
+            } else {
+                self.contract_state.score_count * 10_000 / self.contract_state.user_count
+            },
+        }
+    }
+
+    // gasless breakdown of the storage used by the contract, to help plan deposits
+    // attribution is estimated from the serialized size of the stored entries,
+    // so the figures are approximate, only their total matches env::storage_usage()
+    pub fn storage_report(&self) -> StorageReport {
+        let total = env::storage_usage();
+        // each score is stored under the prefix of its user's vector followed by its index
+        let prefix_len = StorageKey::Accounts { account_hash: vec![0; 32] }.try_to_vec().unwrap().len() as u64;
+        let mut records = 0u64;
+        for account_id in self.accounts.iter() {
+            if let Some(scores) = self.records.get(&account_id) {
+                // the vector itself is stored under the user's account id
+                records += 1 + account_id.len() as u64 + scores.try_to_vec().unwrap().len() as u64;
+                for u in scores.iter() {
+                    records += prefix_len + 8 + u.try_to_vec().unwrap().len() as u64;
+                }
+            }
+        }
+        let records = records.min(total);
+        let counters = (self.contract_state.try_to_vec().unwrap().len() as u64).min(total - records);
+        StorageReport {
+            records,
+            counters,
+            overhead: total - records - counters,
+            total,
+        }
+    }
+
+    // the primary owner: the initial owner, or whoever revoked the previous
+    // primary owner. It may differ from the contract account
+    pub fn get_owner(&self) -> AccountId {
+        self.primary_owner.clone()
+    }
+
+    // all the accounts allowed to administer the contract
+    pub fn get_owners(&self) -> Vec<AccountId> {
+        self.owners.to_vec()
+    }
+
+    // the sha256 digest of an account id that its score history's storage prefix is derived from,
+    // so that indexers can reproduce the storage keys
+    pub fn account_hash(&self, account_id: String) -> Base64VecU8 {
+        Base64VecU8(env::sha256(account_id.as_bytes()))
+    }
+
+    // cheap sanity probe of the invariants tying the counters to the stored data
+    pub fn health_check(&self) -> HealthReport {
+        let mut violations = vec![];
+        let state = &self.contract_state;
+        // every user has at least one score
+        if state.score_count < state.user_count {
+            violations.push(format!("score_count {} is below user_count {}", state.score_count, state.user_count));
+        }
+        if self.accounts.len() != state.user_count {
+            violations.push(format!(
+                "{} accounts are registered but user_count is {}",
+                self.accounts.len(),
+                state.user_count
+            ));
+        }
+        HealthReport { ok: violations.is_empty(), violations }
+    }
+
+    // version of the deployed code
+    pub fn contract_version(&self) -> String {
+        VERSION.to_string()
+    }
+
+    // estimate the storage cost of the next score of an account
+    // a new account also pays for its score history, metadata and membership in the accounts set;
+    // each byte of description adds one byte on top of the estimate
+    pub fn estimate_store_cost(&self, account_id: String) -> CostEstimate {
+        // assuming the account stores its own score, it is the issuer as well
+        let placeholder = env::current_account_id();
+        let entry = User { id: 0, score: 0, timestamp: 0, description: vec![], issuer: placeholder.clone() };
+        let entry_len =
+            entry.try_to_vec().unwrap().len() as u64 - placeholder.as_str().len() as u64 + account_id.len() as u64;
+        let key_prefix = StorageKey::Accounts { account_hash: vec![0; 32] }.try_to_vec().unwrap().len() as u64;
+        // the score itself, stored under the vector's prefix followed by its index
+        let mut bytes = key_prefix + 8 + entry_len + STORAGE_BYTES_PER_RECORD;
+        if !self.accounts.contains(&account_id) {
+            let account_key = 1 + 4 + account_id.len() as u64;
+            // the vector header under the records map
+            bytes += account_key + 8 + 4 + key_prefix + STORAGE_BYTES_PER_RECORD;
+            // the user's metadata
+            bytes += account_key + UserMeta { since: 0, sum: 0 }.try_to_vec().unwrap().len() as u64 + STORAGE_BYTES_PER_RECORD;
+            // the index and element records of the accounts set
+            bytes += 2 * (account_key + 8 + STORAGE_BYTES_PER_RECORD);
+        }
+        CostEstimate {
+            bytes,
+            deposit: U128(env::storage_byte_cost() * bytes as u128),
+        }
+    }
+
+    // health check of the linkage between this oracle and the NFT contract
+    pub fn integration_status(&self) -> IntegrationStatus {
+        IntegrationStatus {
+            nft_contract: self.config.nft_contract.clone(),
+            auto_mint_enabled: self.config.nft_contract.is_some()
+                && self.config.auto_mint_threshold.is_some(),
+            auto_mint_threshold: self.config.auto_mint_threshold,
+        }
+    }
+
+    // query the maximum number of scores a user may store
+    pub fn get_user_cap(&self, account_id: String) -> u16 {
+        self.cap_for(&account_id)
+    }
+
+    // count the distinct accounts a given oracle has submitted scores for
+    pub fn oracle_unique_accounts(&self, oracle: AccountId) -> u64 {
+        match self.oracle_subjects.get(&oracle) {
+            Some(subjects) => subjects.len(),
+            None => 0,
+        }
+    }
+
+    // check whether a user has a score record
+    // the accounts set answers without deserializing the user's score history
+    pub fn user_exist(&self, account_id: String) -> bool {
+        self.accounts.contains(&account_id)
+    }
+
+    // number of users whose latest score is at most max_age_secs old
+    pub fn active_user_count(&self, max_age_secs: u64) -> u64 {
+        let now = env::block_timestamp();
+        let max_age = max_age_secs.saturating_mul(u64::pow(10, 9));
+        self.accounts
+            .iter()
+            .filter_map(|account_id| self.latest_entry(&account_id))
+            .filter(|latest| now.saturating_sub(latest.timestamp) <= max_age)
+            .count() as u64
+    }
+
+    // check a whole list of accounts at once, the result is parallel to the input
+    pub fn users_exist(&self, account_ids: Vec<String>) -> Vec<bool> {
+        if account_ids.len() > MAX_BATCH_SIZE {
+            env::panic_str("ERR_TOO_MANY_ACCOUNTS")
+        }
+        account_ids.iter().map(|a| self.accounts.contains(a)).collect()
+    }
+
+    // whether a user's history is full, i.e. stores as many scores as their cap allows
+    pub fn is_at_cap(&self, account_id: String) -> bool {
+        match self.records.get(&account_id) {
+            Some(scores) => scores.len() >= self.cap_for(&account_id) as u64,
+            None => false,
+        }
+    }
+
+    // return the length of the user's score history
+    pub fn maxout_check(&self, account_id: String) -> u64 {
+        if let Some(i) = self.records.get(&account_id) {
+            let count = i.len();
+            return count;
+        } else {
+            let count: u64 = 0;
+            return count;
+        }
+    }
+}
+
+// --------------------------------------------------------------------- //
+//                            Internal methods                           //
+//                                                                       //
+// ----------------------------------------------------------------------//
+impl Contract {
+    // emit a NEP-297 event, i.e. a log line made of 'EVENT_JSON:' followed by the event as JSON
+    // the standard is the deployment's own namespace, "nearoracle" by default
+    fn log_event(&self, event: &str, data: serde_json::Value) {
+        let log = json!({
+            "standard": self.config.event_standard,
+            "version": EVENT_VERSION,
+            "contract_version": VERSION,
+            "event": event,
+            "data": [data],
+        });
+        env::log_str(&format!("EVENT_JSON:{}", log));
+    }
+
+    // leave an on-chain audit trail of an owner-only mutation
+    fn log_admin_action(&self, action: &str, target: &str) {
+        self.log_event(
+            "admin_action",
+            json!({
+                "action": action,
+                "target": target,
+                "owner": env::predecessor_account_id().to_string(),
+            }),
+        );
+    }
+
+    // panic unless the method is called by one of the contract owners
+    fn assert_owner(&self) {
+        if !self.owners.contains(&env::predecessor_account_id()) {
+            env::panic_str("ERR_ONLY_OWNER")
+        }
+    }
+
+    // panic unless the nonce is strictly greater than the last one the account submitted a score with
+    fn assert_fresh_nonce(&self, account_id: &str, nonce: u64) {
+        if let Some(last) = self.nonces.get(&account_id.to_string()) {
+            if nonce <= last {
+                env::panic_str("ERR_STALE_NONCE")
+            }
+        }
+    }
+
+    // maximum number of scores for a user: their own override if any, or the contract-wide cap
+    fn cap_for(&self, account_id: &str) -> u16 {
+        self.user_caps
+            .get(&account_id.to_string())
+            .unwrap_or(self.config.max_scores)
+    }
+
+    // return the numerical scores of a user, panicking if they have no score history
+    fn score_values(&self, account_id: &str) -> Vec<u16> {
+        let scores: Vec<u16> = match self.records.get(&account_id.to_string()) {
+            Some(history) => history.iter().map(|u| u.score).collect(),
+            None => vec![],
+        };
+        if scores.is_empty() {
+            env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY")
+        }
+        scores
+    }
+
+    // the error store_score would panic with for this input, if any, along with a human-readable reason
+    fn score_input_error(&self, account_id: &str, score: u16, description: &str) -> Option<(&'static str, String)> {
+        if !(MIN_SCORE..=MAX_SCORE).contains(&score) {
+            return Some((
+                "ERR_SCORE_OUT_OF_RANGE",
+                format!("the score must be between {} and {}", MIN_SCORE, MAX_SCORE),
+            ));
+        }
+        if description.as_bytes().len() > self.config.max_desc_len as usize {
+            return Some((
+                "ERR_DESCRIPTION_TOO_LONG",
+                format!("the description must be at most {} bytes long", self.config.max_desc_len),
+            ));
+        }
+        if let Some(scores) = self.records.get(&account_id.to_string()) {
+            let err = "ERR_EXCEEDED_HUNDRED_SCORES_UPPERBOUND_OR_LATEST_SCORE_IS_TOO_RECENT";
+            // when the history is full, a rolling window makes room by dropping the oldest score
+            let cap = self.cap_for(account_id);
+            if scores.len() >= cap as u64 && !self.config.rolling_window {
+                return Some((err, format!("the score history is full ({} scores)", cap)));
+            }
+            if let Some(latest) = self.latest_entry(account_id) {
+                if env::block_timestamp().saturating_sub(latest.timestamp) <= COOLDOWN_NS {
+                    return Some((err, "the latest score is too recent, wait 30 seconds between scores".to_string()));
+                }
+            }
+        }
+        None
+    }
+
+    // store a new score for a given account, creating their score history if needed
+    fn internal_store_score(&mut self, account_id: String, score: u16, description: String) -> ScoreOutcome {
+        // reject invalid input before spending any more gas on it
+        if let Some((err, _)) = self.score_input_error(&account_id, score, &description) {
+            env::panic_str(err)
+        }
+        let new_score = User {
+            id: self.contract_state.next_score_id,
+            score: score,
+            timestamp: env::block_timestamp(),
+            description: description.as_bytes().to_vec(),
+            issuer: env::predecessor_account_id(),
+        };
+
+        let mut success = false;
+        let mut index = 0;
+        // the latest score of a returning user, which the new one gets compared against
+        let mut previous_score = None;
+        let mappy = self.records.get(&account_id);
+        match mappy {
+            // if it's a new user --> create a brand new vector to store their score
+            None => {
+                log!("{} is a new user", account_id);
+                let mut x = Vector::new(
+                    // Every instance of a persistent collection requires a UNIQUE storage prefix,
+                    // so generate a distinct prefix for every user
+                    // the prefix is the enum discriminant followed by the length-prefixed 32-byte hash,
+                    // so it can't overlap with the other collections' prefixes nor with the b"m" records map;
+                    // it is derived once, returning users get their vector back with the prefix it was created with
+                    StorageKey::Accounts { account_hash: env::sha256(account_id.as_bytes()) }
+                );
+                x.push(&new_score);
+                // update the score count iff you succeeded writing it to blockchain`
+                self.records.insert(&account_id, &x);
+                if self.records.insert(&account_id, &x).is_some() {
+                    // remember when the account first showed up
+                    self.user_meta.insert(&account_id, &UserMeta { since: new_score.timestamp, sum: score as u64 });
+                    self.accounts.insert(&account_id);
+                    self.log_event(
+                        "user_registered",
+                        json!({ "account_id": account_id, "timestamp": new_score.timestamp }),
+                    );
+                    self.contract_state.user_count = checked_increase(self.contract_state.user_count, 1);
+                    self.contract_state.score_count = checked_increase(self.contract_state.score_count, 1);
+                    success = true;
+                    log!("Score stored successfully to NEAR blockchain");
+                }
+            }
+
+            // if it's a returning user --> append new score to existing vector
+            Some(i) => {
+                log!("{} is a returning user", account_id);
+                let indx = i.len() - 1;
+                if let Some(j) = i.get(indx) {
+                    let timelapsed = new_score.timestamp - j.timestamp;
+                    // when the history is full, a rolling window makes room by dropping the oldest score
+                    let full = i.len() >= self.cap_for(&account_id) as u64;
+                    // if statement w/ 2 conditions: iff there's room for a new score, iff last score is 30+ days old
+                    if (!full || self.config.rolling_window) && timelapsed > COOLDOWN_NS { // 30 seconds
+                        // && timelapsed > 2592 * u64::pow(10, 12) {  // 30 days
+                        let mut y = i;
+                        let mut dropped = 0;
+                        if full {
+                            // the dropped score leaves the cached sum too
+                            dropped = y.get(0).map(|u| u.score).unwrap_or(0);
+                            drop_oldest_score(&mut y);
+                        }
+                        y.push(&new_score);
+                        self.update_score_sum(&account_id, score, dropped);
+                        index = y.len() - 1;
+                        // update the score count iff you succeeded writing it to chain
+                        self.records.insert(&account_id, &y);
+                        if self.records.insert(&account_id, &y).is_some() {
+                            // a rolling window keeps the total number of stored scores constant
+                            if !full {
+                                self.contract_state.score_count = checked_increase(self.contract_state.score_count, 1);
+                            }
+                            success = true;
+                            previous_score = Some(j.score);
+                            log!("Score stored successfully to NEAR blockchain");
+                        }
+                    } else {
+                        env::panic_str(
+                            "ERR_EXCEEDED_HUNDRED_SCORES_UPPERBOUND_OR_LATEST_SCORE_IS_TOO_RECENT",
+                        )
+                    }
+                }
+            }
+        }
+        // the id is used up only once the score made it to chain
+        if success {
+            self.contract_state.next_score_id = checked_increase(self.contract_state.next_score_id, 1);
+            self.record_recent_score(&account_id, new_score.score, new_score.timestamp);
+            // exactly one event per write: a returning user's carries how much the score moved
+            match previous_score {
+                Some(previous_score) => self.log_event(
+                    "score_updated",
+                    json!({
+                        "account_id": account_id,
+                        "id": new_score.id,
+                        "previous_score": previous_score,
+                        "new_score": new_score.score,
+                        "delta": new_score.score as i32 - previous_score as i32,
+                        "timestamp": new_score.timestamp,
+                    }),
+                ),
+                None => self.log_event(
+                    "score_stored",
+                    json!({
+                        "account_id": account_id,
+                        "id": new_score.id,
+                        "score": new_score.score,
+                        "timestamp": new_score.timestamp,
+                    }),
+                ),
+            }
+        }
+        // return an outcome struct describing whether the
+        // operation of storing a score to blockchain was successful
+        ScoreOutcome {
+            gas_used: env::used_gas(),
+            score_owner: account_id,
+            successful_operation: success,
+            timestamp: new_score.timestamp,
+            index,
+        }
+    }
+
+    // remove a user together with their whole score history
+    fn remove_user(&mut self, account_id: &str) {
+        let account_id = account_id.to_string();
+        let mut scores = self
+            .records
+            .remove(&account_id)
+            .unwrap_or_else(|| env::panic_str("ERR_THIS_USER_HAS_NO_SCORE_HISTORY"));
+        let deleted = scores.len();
+        // clear the vector too, otherwise its elements would linger in storage
+        scores.clear();
+        self.user_meta.remove(&account_id);
+        self.accounts.remove(&account_id);
+        self.contract_state.user_count = checked_decrease(self.contract_state.user_count, 1);
+        self.contract_state.score_count = checked_decrease(self.contract_state.score_count, deleted);
+    }
+
+    // return the most recent score entry of a user, if any
+    fn latest_entry(&self, account_id: &str) -> Option<User> {
+        let scores = self.records.get(&account_id.to_string())?;
+        if scores.is_empty() {
+            return None;
+        }
+        scores.get(scores.len() - 1)
+    }
+
+    // run a write whose storage the caller pays for: accounts with a registered storage balance pay from it,
+    // topped up with the deposit, while the others pay with the deposit, which must cover the minimum deposit;
+    // the deposit is the part of the attached deposit meant for this write, every write entry point goes through here
+    fn paid_write<T>(&mut self, deposit: u128, write: impl FnOnce(&mut Self) -> T) -> T {
+        let registered = self.storage_deposits.get(&env::predecessor_account_id());
+        if registered.is_none() && deposit < self.config.min_deposit.0 {
+            env::panic_str("ERR_DEPOSIT_BELOW_MINIMUM")
+        }
+        let initial_storage_usage = env::storage_usage();
+        let result = write(self);
+        match registered {
+            Some(balance) => self.charge_storage_balance(balance + deposit, initial_storage_usage),
+            None => self.refund_surplus_deposit(deposit, initial_storage_usage),
+        }
+        result
+    }
+
+    // charge the caller the storage used since initial_storage_usage, but no less than the minimum deposit,
+    // and give the rest of the deposit back
+    // without a minimum deposit writes stay free, so a deposit short of the storage cost is kept whole instead
+    fn refund_surplus_deposit(&self, deposit: u128, initial_storage_usage: u64) {
+        let storage_cost =
+            env::storage_byte_cost() * env::storage_usage().saturating_sub(initial_storage_usage) as u128;
+        let charge = storage_cost.max(self.config.min_deposit.0);
+        if deposit < charge {
+            if self.config.min_deposit.0 == 0 {
+                return;
+            }
+            env::panic_str("ERR_INSUFFICIENT_STORAGE_DEPOSIT")
+        }
+        if deposit > charge {
+            Promise::new(env::predecessor_account_id()).transfer(deposit - charge);
+        }
+    }
+
+    // pay the storage used since initial_storage_usage out of the caller's registered storage balance
+    fn charge_storage_balance(&mut self, balance: u128, initial_storage_usage: u64) {
+        let storage_cost =
+            env::storage_byte_cost() * env::storage_usage().saturating_sub(initial_storage_usage) as u128;
+        if balance < storage_cost {
+            env::panic_str("ERR_INSUFFICIENT_STORAGE_BALANCE")
+        }
+        self.storage_deposits.insert(&env::predecessor_account_id(), &(balance - storage_cost));
+    }
+
+    // add a write to the activity feed, overwriting the oldest one once the buffer is full
+    fn record_recent_score(&mut self, account_id: &str, score: u16, timestamp: u64) {
+        let entry = (account_id.to_string(), score, timestamp);
+        if self.recent_scores.len() < MAX_RECENT_SCORES {
+            self.recent_scores.push(&entry);
+        } else {
+            self.recent_scores.replace(self.recent_writes % MAX_RECENT_SCORES, &entry);
+        }
+        self.recent_writes += 1;
+    }
+
+    // keep the cached sum of a user's scores in step with a change to their history
+    fn update_score_sum(&mut self, account_id: &str, added: u16, removed: u16) {
+        if let Some(mut meta) = self.user_meta.get(&account_id.to_string()) {
+            meta.sum = checked_decrease(checked_increase(meta.sum, added as u64), removed as u64);
+            self.user_meta.insert(&account_id.to_string(), &meta);
+        }
+    }
+
+    // return the most recent score of a user, if any
+    fn latest_score(&self, account_id: &str) -> Option<u16> {
+        self.latest_entry(account_id).map(|u| u.score)
+    }
+
+    // test-only setter pushing the score counter to a given value, e.g. right below its limit
+    #[cfg(test)]
+    fn set_score_count(&mut self, score_count: u64) {
+        self.contract_state.score_count = score_count;
+    }
+}
+
+// fixed-point weight 2^(-age/half_life) scaled by 2^40, halving once per whole half-life
+// and interpolating linearly within the current one, so that no floats are involved
+fn decay_weight(age: u64, half_life: u64) -> u128 {
+    let halvings = age / half_life;
+    if halvings >= 40 {
+        return 0;
+    }
+    let weight: u128 = (1u128 << 40) >> halvings;
+    let rem = (age % half_life) as u128;
+    weight - weight * rem / (2 * half_life as u128)
+}
+
+// map a score from [MIN_SCORE, MAX_SCORE] onto [0, 100], rounding to the nearest integer
+// scores outside the band are clamped onto its ends; the band is fixed at compile time, so the span is never 0
+fn normalize_score(score: u16) -> u8 {
+    let span = (MAX_SCORE - MIN_SCORE) as u32;
+    let offset = (score.clamp(MIN_SCORE, MAX_SCORE) - MIN_SCORE) as u32;
+    ((offset * 100 + span / 2) / span) as u8
+}
+
+// add to one of the contract's counters, panicking rather than silently wrapping around
+fn checked_increase(counter: u64, amount: u64) -> u64 {
+    counter
+        .checked_add(amount)
+        .unwrap_or_else(|| env::panic_str("ERR_COUNTER_OVERFLOW"))
+}
+
+// subtract from one of the contract's counters, panicking rather than silently wrapping around
+fn checked_decrease(counter: u64, amount: u64) -> u64 {
+    counter
+        .checked_sub(amount)
+        .unwrap_or_else(|| env::panic_str("ERR_COUNTER_OVERFLOW"))
+}
+
+// median of a non-empty list of scores, averaging the two middle values of an even-sized list
+fn median(scores: &[u16]) -> u16 {
+    let mut sorted = scores.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 0 {
+        ((sorted[mid - 1] as u32 + sorted[mid] as u32) / 2) as u16
+    } else {
+        sorted[mid]
+    }
+}
+
+// remove the first (oldest) score of a history, shifting the remaining scores down by one
+fn drop_oldest_score(scores: &mut Vector<User>) {
+    for k in 1..scores.len() {
+        let next = scores.get(k).unwrap();
+        scores.replace(k - 1, &next);
+    }
+    scores.pop();
+}
+
+// nanosecond timestamp of midnight UTC on the first day of a month
+// the days-from-civil conversion treats March as the first month of the year,
+// so that the leap day always falls at the very end of the (shifted) year
+fn month_start_ns(year: u64, month: u8) -> u64 {
+    let month = month as u64;
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y / 400;
+    let year_of_era = y - era * 400;
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    // 719468 is the number of days between 0000-03-01 and 1970-01-01
+    let days = era * 146_097 + day_of_era - 719_468;
+    days * NS_PER_DAY
+}
+
+/*
+ * the rest of this file sets up unit tests
+ * execute them running the command:
+ * cargo test --package near_oracle -- --nocapture
+ * Note: 'near_oracle' comes from Cargo.toml's 'name' key
+ */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, RuntimeFeesConfig, VMConfig, VMContext};
+    use std::convert::TryInto;
+
+    // define 3 fake users
+    fn doomslug() -> AccountId {
+        "doomslug.testnet".to_string().try_into().unwrap()
+    }
+
+    fn spensa() -> AccountId {
+        "spensa.testnet".to_string().try_into().unwrap()
+    }
+
+    fn rainbow() -> AccountId {
+        "rainbow.testnet".to_string().try_into().unwrap()
+    }
+
+    // part of writing unit tests is setting up a mock context
+    // provide a `predecessor` here, it'll modify the default context
+    fn get_context(is_view: bool, predecessor: AccountId ) -> VMContext {
+        VMContextBuilder::new()
+            // set 'spensa.testnet' to be the contract owner
+            .current_account_id("spensa.testnet".to_string().try_into().unwrap())
+            .predecessor_account_id(predecessor)
+            .block_timestamp(0u64)
+            .storage_usage(0u64)
+            .is_view(is_view)
+            .build()
+    }
+
+    // same as get_context() but with a custom block timestamp, to simulate the passing of time
+    fn get_timed_context(predecessor: AccountId, timestamp: u64) -> VMContext {
+        VMContextBuilder::new()
+            .current_account_id("spensa.testnet".to_string().try_into().unwrap())
+            .predecessor_account_id(predecessor)
+            .block_timestamp(timestamp)
+            .storage_usage(0u64)
+            .is_view(false)
+            .build()
+    }
+
+    // implement two methods to return the length and to index the vector in the MyScoreHistory struct
+    impl MyScoreHistory {
+        fn len(&self) -> usize {
+            self.scores.len()
+        }
+    }
+
+    #[test]
+    fn initialize_stats() {
+        let context = get_context(true, spensa());
+        testing_env!(context);
+        let contract = Contract::new(spensa());
+
+        // ensure that 'Contract' parameters are empty or null at initialization
+        assert_eq!(
+            0, contract.contract_state.user_count,
+            "ERR: User count should be 0 at initialization"
+        );
+        assert_eq!(
+            0, contract.contract_state.score_count,
+            "ERR: Score count should be 0 at initialization"
+        );
+        assert_eq!(
+            contract.get_owner(),
+            spensa(),
+            "ERR: owner ids should coincide"
+        );
+    }
+
+    #[test]
+    fn store_multiple_scores() {
+        let context = get_context(false, doomslug());
+        testing_env!(context);
+        let mut contract = Contract::new(spensa());
+
+        // check initialization values are correct
+        assert_eq!(0, contract.contract_state.user_count);
+        assert_eq!(0, contract.contract_state.score_count);
+        assert_eq!(
+            doomslug().to_string(),
+            String::from(env::predecessor_account_id())
+        );
+
+ 
+        /*
+        * we will now simulate the interaction of three distinct users with the contract:
+        * user 1 will store 1 score to chain (username: doomslug)
+        * user 2 will store 2 scores to chain (username: spensa)
+        * user 3 will store 3 scores to chain (username: rainbow)
+        */
+
+        // -------------- //
+        //      user 1    //
+        // -------------- //
+        // store first score
+        let msg1 = "Sorry, your score is only 300 points".to_string();
+        let out1 = contract.store_score(300, msg1, 1);
+        assert!(out1.successful_operation);
+        assert_eq!(String::from(env::predecessor_account_id()), out1.score_owner);
+
+        // ensure stats was incremented accordingly
+        let state1 = contract.read_state();
+        assert_eq!(1, state1.user_count, "ERR: should be 1 user");
+        assert_eq!(1, state1.score_count, "ERR: should be 1 score");
+
+        // -------------- //
+        //      user 2    //
+        // -------------- //
+        // create a new context with a new predecessor for user #2: spensa
+        let context2 = get_context(false, spensa());
+        testing_env!(context2);
+
+        // store second score
+        let msg2 = "Well done, your score is 501 points".to_string();
+        let out2 = contract.store_score(501, msg2, 2);
+        assert!(out2.successful_operation);
+
+        // ensure again stats was incremented accordingly
+        assert_eq!("spensa.testnet".to_string(), out2.score_owner);
+        assert_eq!(2, contract.contract_state.user_count, "ERR: should be 2 users now");
+        assert_eq!(2, contract.contract_state.score_count, "ERR: should be 2 scores now");
+
+        // store third score, once the cooldown since the second one has passed
+        testing_env!(get_timed_context(spensa(), COOLDOWN_NS + 1));
+        let msg3 = "You improved to 502 points".to_string();
+        let out3 = contract.store_score(502, msg3, 3);
+        assert!(out3.successful_operation);
+
+        // check stats
+        assert_eq!("spensa.testnet".to_string(), out3.score_owner);
+        assert_eq!(2, contract.contract_state.user_count, "ERR: should remain 2 users");
+        assert_eq!(3, contract.contract_state.score_count, "ERR: expected 3 scores");
+
+        // -------------- //
+        //      user 3    //
+        // -------------- //
+        // create a third context with a new predecessor for user #3: rainbow
+        let context3 = get_context(false, rainbow());
+        testing_env!(context3);
+
+        // store a fourth, fifth, sixth score, each past the cooldown of the previous one
+        contract.store_score(701, "Score of 701".to_string(), 4);
+        testing_env!(get_timed_context(rainbow(), COOLDOWN_NS + 1));
+        contract.store_score(702, "Score of 702".to_string(), 5);
+        testing_env!(get_timed_context(rainbow(), 2 * (COOLDOWN_NS + 1)));
+        contract.store_score(703, "Score of 703".to_string(), 6);
+
+        // check stats
+        assert_eq!(3, contract.contract_state.user_count, "ERR: expected 3 users");
+        assert_eq!(6, contract.contract_state.score_count, "ERR: expected 6 scores");
+
+        // check on-chain score record
+        // .contains_key() returns true if the LookupMap 'records' contains a score record for a user
+        assert!(contract.records.contains_key(&"doomslug.testnet".to_string())); 
+        assert!(contract.records.contains_key(&"spensa.testnet".to_string()));
+        assert!(contract.records.contains_key(&"rainbow.testnet".to_string()));
+        assert!(!contract.records.contains_key(&"nightshade.testnet".to_string()));
+
+        // query all scores
+        let user1 = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        let user2 = contract.query_score_history("spensa.testnet".to_string(), None, None, None);
+        let user3 = contract.query_score_history("rainbow.testnet".to_string(), None, None, None);
+        assert_eq!(1, user1.len(), "ERR: only 1 score for user 1");
+        assert_eq!(2, user2.len(), "ERR: expected 2 scores for user 2");
+        assert_eq!(3, user3.len(), "ERR: expected 3 scores for user 3");
+
+        // ensure nuemrical scores are stored under the right user
+        assert_ne!(user1.scores[0].score, user2.scores[1].score);
+        assert_ne!(user3.scores[1].score, user3.scores[2].score);
+        assert_ne!(user2.scores[1].score, user3.scores[2].score);
+        assert_eq!(502, user2.scores[1].score);
+        assert_eq!(703, user3.scores[2].score);
+    }
+
+
+    #[test]
+    fn query_scores_and_state() {
+        let context = get_context(false, rainbow());
+        testing_env!(context);
+        let mut contract = Contract::new(spensa());
+
+        // store 3 scores to blockchain first, each past the cooldown of the previous one
+        let msg3 = "Score of 330";
+        contract.store_score(310, "Score of 310".to_string(), 1);
+        testing_env!(get_timed_context(rainbow(), COOLDOWN_NS + 1));
+        contract.store_score(320, "Score of 320".to_string(), 2);
+        testing_env!(get_timed_context(rainbow(), 2 * (COOLDOWN_NS + 1)));
+        // a new context resets the storage usage, so measure it from here
+        let init_size = contract.read_state().size_now;
+        let out = contract.store_score(330, msg3.to_string(), 3);
+        assert!(init_size < contract.read_state().size_now, "ERR: contract bytesize should increase when storing data");
+
+        // query the 3 scores
+        let user0 = contract.query_score_history("rainbow.testnet".to_string(), None, None, None);
+        assert_eq!(3, user0.scores.len(), "ERR: expected 3 scores");
+        assert!(!contract.records.get(&"rainbow.testnet".to_string()).is_none());
+        assert_eq!(320, user0.scores[1].score, "ERR: mismatchig scores");
+        assert!(user0.scores[0].timestamp < user0.scores[1].timestamp, "ERR: expected increasing timestamps");
+        assert_ne!(user0.scores[0].description, user0.scores[1].description, "ERR: expected different sha256 encryptions");
+
+        // call read_state() to check stats
+        assert_eq!(1, contract.read_state().user_count, "ERR: expected 1 user");
+        assert_eq!(3, contract.read_state().score_count, "ERR: expected 3 scores");
+        assert!(contract.read_state().timestamp == env::block_timestamp(), "ERR: timestamp now should be identical to the contract timestamp");
+
+        // ensure contract owner and score owner are correct
+        assert_eq!("rainbow.testnet".to_string(), out.score_owner, "ERR: mismatching score owners");
+        assert_eq!("spensa.testnet", contract.read_state().owner, "ERR: mismatching contract owners");
+        assert_eq!("spensa.testnet".to_string(), String::from(contract.get_owner()), "ERR: mismatching contract owners");
+    }
+
+    #[test]
+    fn compare_score_against_peer_group() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(700, "Score of 700".to_string(), 1);
+        testing_env!(get_context(false, spensa()));
+        contract.store_score(500, "Score of 500".to_string(), 2);
+        testing_env!(get_context(false, rainbow()));
+        contract.store_score(600, "Score of 600".to_string(), 3);
+
+        // the peer group average is (500 + 600) / 2 = 550, the unknown peer is skipped
+        let group = vec![
+            "spensa.testnet".to_string(),
+            "rainbow.testnet".to_string(),
+            "nightshade.testnet".to_string(),
+        ];
+        assert_eq!(150, contract.score_vs_group("doomslug.testnet".to_string(), group.clone()));
+        assert_eq!(-50, contract.score_vs_group("spensa.testnet".to_string(), group));
+    }
+
+    #[test]
+    fn track_first_seen_timestamp() {
+        let first_write = 1_000 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), first_write));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(400, "Score of 400".to_string(), 1);
+        assert_eq!(first_write, contract.user_since("doomslug.testnet".to_string()));
+
+        // a later write must not move the first-seen timestamp
+        testing_env!(get_timed_context(doomslug(), first_write + 60 * u64::pow(10, 9)));
+        contract.store_score(450, "Score of 450".to_string(), 2);
+        assert_eq!(2, contract.maxout_check("doomslug.testnet".to_string()));
+        assert_eq!(first_write, contract.user_since("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn count_scores_in_calendar_month() {
+        // 2022-01-15 and 2022-02-10, as nanoseconds since the unix epoch
+        let mid_january = 19_007 * NS_PER_DAY;
+        let mid_february = 19_033 * NS_PER_DAY;
+
+        testing_env!(get_timed_context(rainbow(), mid_january));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(610, "Score of 610".to_string(), 1);
+        testing_env!(get_timed_context(rainbow(), mid_february));
+        contract.store_score(620, "Score of 620".to_string(), 2);
+
+        assert_eq!(18_993 * NS_PER_DAY, month_start_ns(2022, 1));
+        assert_eq!(19_052 * NS_PER_DAY, month_start_ns(2022, 3));
+        assert_eq!(1, contract.scores_in_month("rainbow.testnet".to_string(), 2022, 1));
+        assert_eq!(1, contract.scores_in_month("rainbow.testnet".to_string(), 2022, 2));
+        assert_eq!(0, contract.scores_in_month("rainbow.testnet".to_string(), 2022, 12));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_MONTH")]
+    fn reject_invalid_month() {
+        testing_env!(get_context(false, rainbow()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(610, "Score of 610".to_string(), 1);
+        contract.scores_in_month("rainbow.testnet".to_string(), 2022, 13);
+    }
+
+    #[test]
+    fn query_upward_and_downward_trend() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(510, "Score of 510".to_string(), 2);
+        assert_eq!(10, contract.query_score_trend("doomslug.testnet".to_string()));
+
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score(490, "Score of 490".to_string(), 3);
+        assert_eq!(-20, contract.query_score_trend("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn query_trend_of_single_score() {
+        testing_env!(get_context(false, rainbow()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(700, "Score of 700".to_string(), 1);
+        assert_eq!(0, contract.query_score_trend("rainbow.testnet".to_string()));
+    }
+
+    #[test]
+    fn rolling_window_drops_oldest_score() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(rainbow(), 0));
+        let config = Config {
+            max_scores: 3,
+            rolling_window: true,
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+
+        // store four scores, one minute apart, on a history capped at three
+        for (k, score) in [410u16, 420, 430, 440].iter().enumerate() {
+            testing_env!(get_timed_context(rainbow(), k as u64 * minute));
+            assert!(contract.store_score(*score, format!("Score of {}", score), k as u64 + 1).successful_operation);
+        }
+
+        // the oldest score was dropped and the length stayed at the cap
+        let history = contract.query_score_history("rainbow.testnet".to_string(), None, None, None);
+        assert_eq!(3, history.len());
+        assert_eq!(420, history.scores[0].score);
+        assert_eq!(440, history.scores[2].score);
+        assert_eq!(3, contract.read_state().score_count);
+    }
+
+    #[test]
+    fn report_integration_status() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        let before = contract.integration_status();
+        assert!(before.nft_contract.is_none());
+        assert!(!before.auto_mint_enabled);
+        assert!(before.auto_mint_threshold.is_none());
+
+        let nft: AccountId = "nft.spensa.testnet".to_string().try_into().unwrap();
+        contract.set_nft_contract(Some(nft.clone()));
+        assert_eq!(Some(nft.clone()), contract.integration_status().nft_contract);
+        assert!(!contract.integration_status().auto_mint_enabled);
+
+        contract.set_auto_mint_threshold(Some(650));
+        let after = contract.integration_status();
+        assert_eq!(Some(nft), after.nft_contract);
+        assert!(after.auto_mint_enabled);
+        assert_eq!(Some(650), after.auto_mint_threshold);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_ONLY_OWNER")]
+    fn only_owner_links_nft_contract() {
+        testing_env!(get_context(false, rainbow()));
+        let mut contract = Contract::new(spensa());
+        contract.set_nft_contract(Some(rainbow()));
+    }
+
+    #[test]
+    fn share_of_scores_above_median() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+
+        // a skewed history: three low scores and two much higher ones
+        for (k, score) in [300u16, 300, 300, 800, 900].iter().enumerate() {
+            testing_env!(get_timed_context(doomslug(), k as u64 * minute));
+            contract.store_score(*score, format!("Score of {}", score), k as u64 + 1);
+        }
+
+        assert_eq!(300, contract.query_median_score("doomslug.testnet".to_string()));
+        assert_eq!(40, contract.share_above_personal_median("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn set_user_caps_in_batch() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.set_user_caps_batch(vec![
+            ("doomslug.testnet".to_string(), 150),
+            ("rainbow.testnet".to_string(), 200),
+        ]);
+
+        assert_eq!(150, contract.get_user_cap("doomslug.testnet".to_string()));
+        assert_eq!(200, contract.get_user_cap("rainbow.testnet".to_string()));
+        // accounts without an override keep the contract-wide cap
+        assert_eq!(MAX_SCORES, contract.get_user_cap("nightshade.testnet".to_string()));
+    }
+
+    #[test]
+    fn count_accounts_scored_by_oracle() {
+        let oracle: AccountId = "oracle.testnet".to_string().try_into().unwrap();
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.add_oracle(oracle.clone());
+
+        testing_env!(get_timed_context(oracle.clone(), 0));
+        contract.store_score_for("doomslug.testnet".to_string(), 500, "Score of 500".to_string());
+        contract.store_score_for("rainbow.testnet".to_string(), 600, "Score of 600".to_string());
+        assert_eq!(2, contract.oracle_unique_accounts(oracle.clone()));
+
+        // re-scoring a known account doesn't change the count
+        testing_env!(get_timed_context(oracle.clone(), 60 * u64::pow(10, 9)));
+        contract.store_score_for("doomslug.testnet".to_string(), 510, "Score of 510".to_string());
+        assert_eq!(2, contract.oracle_unique_accounts(oracle));
+        assert_eq!(2, contract.maxout_check("doomslug.testnet".to_string()));
+        assert_eq!(0, contract.oracle_unique_accounts(rainbow()));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_AUTHORIZED_ORACLE")]
+    fn reject_unregistered_oracle() {
+        testing_env!(get_context(false, rainbow()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score_for("doomslug.testnet".to_string(), 500, "Score of 500".to_string());
+    }
+
+    #[test]
+    fn export_scores_as_csv() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(spensa(), minute));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(550, "Score of 550".to_string(), 1);
+        testing_env!(get_timed_context(spensa(), 3 * minute));
+        contract.store_score(580, "Score of 580".to_string(), 2);
+
+        let csv = contract.query_scores_csv("spensa.testnet".to_string());
+        let mut lines = csv.lines();
+        assert_eq!(Some("timestamp,score"), lines.next());
+
+        // every row matches the score history, in the same order
+        let history = contract.query_score_history("spensa.testnet".to_string(), None, None, None);
+        let rows: Vec<(u64, u16)> = lines
+            .map(|l| {
+                let mut fields = l.split(',');
+                let timestamp = fields.next().unwrap().parse().unwrap();
+                let score = fields.next().unwrap().parse().unwrap();
+                (timestamp, score)
+            })
+            .collect();
+        assert_eq!(history.len(), rows.len());
+        for (row, s) in rows.iter().zip(history.scores.iter()) {
+            assert_eq!((s.timestamp, s.score), *row);
+        }
+    }
+
+    #[test]
+    fn query_qualified_score() {
+        let day = 86_400 * u64::pow(10, 9);
+        testing_env!(get_timed_context(rainbow(), day));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(650, "Score of 650".to_string(), 1);
+
+        // qualifying: high enough and fresh enough
+        assert_eq!(Some(650), contract.qualified_score("rainbow.testnet".to_string(), 600, 3_600));
+        // too low
+        assert_eq!(None, contract.qualified_score("rainbow.testnet".to_string(), 700, 3_600));
+
+        // too old: two days later, with a one-day freshness window
+        testing_env!(get_timed_context(rainbow(), 3 * day));
+        assert_eq!(None, contract.qualified_score("rainbow.testnet".to_string(), 600, 86_400));
+        assert_eq!(Some(650), contract.qualified_score("rainbow.testnet".to_string(), 600, 2 * 86_400));
+    }
+
+    #[test]
+    fn delete_user_emits_admin_action() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+
+        testing_env!(get_context(false, spensa()));
+        contract.delete_user("doomslug.testnet".to_string());
+        assert!(!contract.user_exist("doomslug.testnet".to_string()));
+        assert_eq!(0, contract.read_state().user_count);
+        assert_eq!(0, contract.read_state().score_count);
+
+        // the deletion left a structured audit trail
+        let logs = get_logs();
+        let last = logs.last().unwrap();
+        assert!(last.starts_with("EVENT_JSON:"));
+        let event: serde_json::Value = serde_json::from_str(&last["EVENT_JSON:".len()..]).unwrap();
+        assert_eq!("nearoracle", event["standard"]);
+        assert_eq!("admin_action", event["event"]);
+        assert_eq!("delete_user", event["data"][0]["action"]);
+        assert_eq!("doomslug.testnet", event["data"][0]["target"]);
+        assert_eq!("spensa.testnet", event["data"][0]["owner"]);
+    }
+
+    #[test]
+    fn query_latest_score_of_user() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(640, "Score of 640".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(720, "Score of 720".to_string(), 2);
+        assert_eq!(720, contract.query_latest_score("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn store_score_and_mint_nft() {
+        testing_env!(get_context(false, doomslug()));
+        let config = Config {
+            nft_contract: Some(rainbow()),
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        contract.store_score_and_mint(720, "Score of 720".to_string(), "score-1".to_string());
+
+        // the score is committed before the NFT contract gets called
+        assert_eq!(720, contract.query_latest_score("doomslug.testnet".to_string()));
+        assert_eq!(2, get_created_receipts().len());
+
+        // mock the NFT contract minting the token
+        testing_env!(
+            get_context(false, spensa()),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(vec![])],
+        );
+        assert!(contract.on_score_nft_minted("doomslug.testnet".to_string(), "score-1".to_string()));
+    }
+
+    #[test]
+    fn surface_failed_mint_in_logs() {
+        testing_env!(
+            get_context(false, spensa()),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        let mut contract = Contract::new(spensa());
+        assert!(!contract.on_score_nft_minted("doomslug.testnet".to_string(), "score-1".to_string()));
+        assert!(get_logs()[0].starts_with("ERR_NFT_MINT_FAILED"));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NFT_CONTRACT_NOT_SET")]
+    fn store_score_and_mint_without_nft_contract() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score_and_mint(720, "Score of 720".to_string(), "score-1".to_string());
+    }
+
+    #[test]
+    fn report_scores_per_user() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_context(true, spensa()));
+        let mut contract = Contract::new(spensa());
+        assert_eq!(0, contract.read_state().scores_per_user);
+
+        // 3 users storing 1, 2 and 3 scores respectively
+        for (k, user) in [doomslug(), spensa(), rainbow()].iter().enumerate() {
+            for t in 0..=k as u64 {
+                testing_env!(get_timed_context(user.clone(), t * minute));
+                contract.store_score(500, "Score of 500".to_string(), t + 1);
+            }
+        }
+        let state = contract.read_state();
+        assert_eq!(3, state.user_count);
+        assert_eq!(6, state.score_count);
+        // 2.0 scores per user, in basis points
+        assert_eq!(20_000, state.scores_per_user);
+    }
+
+    #[test]
+    fn assign_unique_score_ids() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(spensa(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score(700, "Score of 700".to_string(), 3);
+
+        let doomslug_ids: Vec<u64> = contract
+            .query_score_history("doomslug.testnet".to_string(), None, None, None)
+            .scores
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        let spensa_ids: Vec<u64> = contract
+            .query_score_history("spensa.testnet".to_string(), None, None, None)
+            .scores
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        // ids follow the order in which scores got stored, across users
+        assert_eq!(vec![0, 2], doomslug_ids);
+        assert_eq!(vec![1], spensa_ids);
+    }
+
+    #[test]
+    fn query_score_at_index() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+
+        let second = contract.query_score_at("doomslug.testnet".to_string(), 1);
+        assert_eq!(600, second.score);
+        assert_eq!(minute, second.timestamp);
+        assert_eq!(b"Score of 600".to_vec(), second.description);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INDEX_OUT_OF_BOUNDS")]
+    fn query_score_at_index_out_of_bounds() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        contract.query_score_at("doomslug.testnet".to_string(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_THIS_USER_HAS_NO_SCORE_HISTORY")]
+    fn query_score_at_of_unknown_user() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(spensa());
+        contract.query_score_at("doomslug.testnet".to_string(), 0);
+    }
+
+    #[test]
+    fn query_score_history_newest_first() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        for (t, score) in [500, 600, 700].iter().enumerate() {
+            testing_env!(get_timed_context(doomslug(), t as u64 * minute));
+            contract.store_score(*score, format!("Score of {}", score), t as u64 + 1);
+        }
+
+        let history = contract.query_score_history("doomslug.testnet".to_string(), None, None, Some(true));
+        let scores: Vec<u16> = history.scores.iter().map(|s| s.score).collect();
+        assert_eq!(vec![700, 600, 500], scores);
+
+        // pages count from the newest end when reversed
+        let page = contract.query_score_history("doomslug.testnet".to_string(), Some(1), Some(1), Some(true));
+        assert_eq!(1, page.len());
+        assert_eq!(600, page.scores[0].score);
+    }
+
+    #[test]
+    fn bin_latest_scores_into_buckets() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(310, "Score of 310".to_string(), 1);
+        testing_env!(get_context(false, spensa()));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+        testing_env!(get_context(false, rainbow()));
+        contract.store_score(890, "Score of 890".to_string(), 3);
+
+        assert_eq!(vec![1, 1, 1], contract.score_distribution(3));
+        assert_eq!(vec![3], contract.score_distribution(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_BUCKET_COUNT")]
+    fn reject_invalid_bucket_count() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(spensa());
+        contract.score_distribution(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_COUNTER_OVERFLOW")]
+    fn guard_score_count_overflow() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.set_score_count(u64::MAX);
+        contract.store_score(500, "Score of 500".to_string(), 1);
+    }
+
+    #[test]
+    fn rank_most_improved_users() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+        testing_env!(get_timed_context(spensa(), 0));
+        contract.store_score(700, "Score of 700".to_string(), 3);
+        testing_env!(get_timed_context(spensa(), minute));
+        contract.store_score(650, "Score of 650".to_string(), 4);
+
+        let ranking = contract.most_improved(10);
+        assert_eq!(1, ranking.len());
+        assert_eq!("doomslug.testnet", ranking[0].account_id);
+        assert_eq!(600, ranking[0].score);
+        assert_eq!(100, ranking[0].delta);
+    }
+
+    #[test]
+    fn outcome_reports_timestamp_and_index() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), minute));
+        let mut contract = Contract::new(spensa());
+        let first = contract.store_score(500, "Score of 500".to_string(), 1);
+        assert_eq!(0, first.index);
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        let second = contract.store_score(600, "Score of 600".to_string(), 2);
+
+        let stored = contract.query_score_at("doomslug.testnet".to_string(), second.index);
+        assert_eq!(1, second.index);
+        assert_eq!(stored.timestamp, second.timestamp);
+        assert_eq!(600, stored.score);
+        assert_eq!(first.timestamp, contract.query_score_at("doomslug.testnet".to_string(), 0).timestamp);
+    }
+
+    #[test]
+    fn export_user_history_as_json() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+
+        let export: serde_json::Value =
+            serde_json::from_str(&contract.export_user_json("doomslug.testnet".to_string())).unwrap();
+        let scores = export["scores"].as_array().unwrap();
+        assert_eq!(2, scores.len());
+        assert_eq!(600, scores[1]["score"]);
+        assert_eq!(hex::encode(env::sha256(b"Score of 600")), scores[1]["description"]);
+    }
+
+    #[test]
+    fn count_distinct_descriptions() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Keep it up".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(520, "Keep it up".to_string(), 2);
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score(540, "Score of 540".to_string(), 3);
+        assert_eq!(2, contract.distinct_descriptions_for("doomslug.testnet".to_string()));
+    }
+
+    // the NEP-297 events of a given kind among the logs of the latest call
+    fn events_named(event: &str) -> Vec<serde_json::Value> {
+        get_logs()
+            .iter()
+            .filter(|log| log.starts_with("EVENT_JSON:"))
+            .map(|log| serde_json::from_str::<serde_json::Value>(&log["EVENT_JSON:".len()..]).unwrap())
+            .filter(|e| e["event"] == event)
+            .collect()
+    }
+
+    #[test]
+    fn register_new_user_once() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), minute));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        let registered = events_named("user_registered");
+        assert_eq!(1, registered.len());
+        assert_eq!("doomslug.testnet", registered[0]["data"][0]["account_id"]);
+        assert_eq!(minute, registered[0]["data"][0]["timestamp"]);
+        assert_eq!(1, events_named("score_stored").len());
+
+        // a returning user only updates its score
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+        assert!(events_named("user_registered").is_empty());
+        assert_eq!(1, events_named("score_updated").len());
+    }
+
+    #[test]
+    fn accept_description_at_length_limit() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        let outcome = contract.store_score(500, "a".repeat(MAX_DESC_LEN as usize), 1);
+        assert!(outcome.successful_operation);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DESCRIPTION_TOO_LONG")]
+    fn reject_description_over_length_limit() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "a".repeat(MAX_DESC_LEN as usize + 1), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DESCRIPTION_TOO_LONG")]
+    fn override_description_length_limit() {
+        testing_env!(get_context(false, doomslug()));
+        let config = Config {
+            max_desc_len: 8,
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        contract.store_score(500, "Score of 500".to_string(), 1);
+    }
+
+    #[test]
+    fn query_score_held_at_timestamp() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), minute));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), 3 * minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+
+        let account_id = "doomslug.testnet".to_string();
+        assert!(contract.score_at_timestamp(account_id.clone(), minute - 1).is_none());
+        assert_eq!(500, contract.score_at_timestamp(account_id.clone(), 2 * minute).unwrap().score);
+        assert_eq!(600, contract.score_at_timestamp(account_id, 10 * minute).unwrap().score);
+    }
+
+    #[test]
+    fn report_storage_breakdown() {
+        // the report is asked for in the same context, so that the storage usage carries over to it
+        // anyone can ask for it, not only the owner
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+
+        let report = contract.storage_report();
+        assert!(report.records > 0);
+        assert!(report.total <= env::storage_usage());
+        assert_eq!(report.total, report.records + report.counters + report.overhead);
+    }
+
+    #[test]
+    fn retract_score_within_grace_period() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), 10 * minute));
+        contract.store_score(900, "Oops".to_string(), 2);
+
+        testing_env!(get_timed_context(doomslug(), 14 * minute));
+        contract.retract_latest_score();
+        assert_eq!(500, contract.query_latest_score("doomslug.testnet".to_string()));
+        assert_eq!(1, contract.read_state().score_count);
+
+        // retracting the only score left removes the user
+        testing_env!(get_timed_context(rainbow(), 0));
+        contract.store_score(600, "Score of 600".to_string(), 3);
+        contract.retract_latest_score();
+        assert!(!contract.user_exist("rainbow.testnet".to_string()));
+        assert_eq!(1, contract.read_state().user_count);
+        assert_eq!(1, contract.read_state().score_count);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_GRACE_EXPIRED")]
+    fn retract_score_after_grace_period() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), 5 * minute));
+        contract.retract_latest_score();
+    }
+
+    // the oracle's attestation key pair, derived from a fixed seed
+    fn attestation_keypair() -> ed25519_dalek::Keypair {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        ed25519_dalek::Keypair { secret, public }
+    }
+
+    // sign an attestation for a given deployment the way the off-chain oracle does
+    fn sign_attestation_for(contract_id: &str, account_id: &str, score: u16, timestamp: u64, nonce: u64) -> Base64VecU8 {
+        use ed25519_dalek::Signer;
+        let message = format!("{}:{}:{}:{}:{}", contract_id, account_id, score, timestamp, nonce);
+        Base64VecU8(attestation_keypair().sign(message.as_bytes()).to_bytes().to_vec())
+    }
+
+    // sign an attestation for the contract deployed to 'spensa.testnet'
+    fn sign_attestation(account_id: &str, score: u16, timestamp: u64, nonce: u64) -> Base64VecU8 {
+        sign_attestation_for("spensa.testnet", account_id, score, timestamp, nonce)
+    }
+
+    // a contract owned by 'spensa.testnet' trusting the oracle's attestation key
+    fn setup_attested_contract() -> (Contract, Base64VecU8) {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        let pubkey = Base64VecU8(attestation_keypair().public.to_bytes().to_vec());
+        contract.add_attestation_key(pubkey.clone());
+        (contract, pubkey)
+    }
+
+    #[test]
+    fn store_signed_attestation() {
+        let (mut contract, pubkey) = setup_attested_contract();
+        testing_env!(get_context(false, doomslug()));
+        let signature = sign_attestation("doomslug.testnet", 720, 1_000, 1);
+        let outcome = contract.store_signed_score(720, 1_000, 1, signature, pubkey);
+        assert!(outcome.successful_operation);
+        assert_eq!(720, contract.query_latest_score("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_BAD_SIGNATURE")]
+    fn reject_tampered_attestation() {
+        let (mut contract, pubkey) = setup_attested_contract();
+        testing_env!(get_context(false, doomslug()));
+        // the score got bumped after signing
+        let signature = sign_attestation("doomslug.testnet", 620, 1_000, 1);
+        contract.store_signed_score(720, 1_000, 1, signature, pubkey);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_UNKNOWN_ATTESTATION_KEY")]
+    fn reject_unregistered_attestation_key() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        let pubkey = Base64VecU8(attestation_keypair().public.to_bytes().to_vec());
+        let signature = sign_attestation("doomslug.testnet", 720, 1_000, 1);
+        contract.store_signed_score(720, 1_000, 1, signature, pubkey);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_BAD_SIGNATURE")]
+    fn reject_attestation_for_other_deployment() {
+        let (mut contract, pubkey) = setup_attested_contract();
+        testing_env!(get_context(false, doomslug()));
+        let signature = sign_attestation_for("other-oracle.testnet", "doomslug.testnet", 720, 1_000, 1);
+        contract.store_signed_score(720, 1_000, 1, signature, pubkey);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_STALE_NONCE")]
+    fn reject_replayed_attestation() {
+        let (mut contract, pubkey) = setup_attested_contract();
+        testing_env!(get_timed_context(doomslug(), 0));
+        let signature = sign_attestation("doomslug.testnet", 720, 1_000, 1);
+        contract.store_signed_score(720, 1_000, 1, signature.clone(), pubkey.clone());
+        testing_env!(get_timed_context(doomslug(), 60 * u64::pow(10, 9)));
+        contract.store_signed_score(720, 1_000, 1, signature, pubkey);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_STALE_ATTESTATION")]
+    fn reject_attestation_older_than_previous_one() {
+        let (mut contract, pubkey) = setup_attested_contract();
+        testing_env!(get_timed_context(doomslug(), 0));
+        let signature = sign_attestation("doomslug.testnet", 720, 2_000, 1);
+        contract.store_signed_score(720, 2_000, 1, signature, pubkey.clone());
+        // a fresh nonce doesn't make up for an attestation issued before the previous one
+        testing_env!(get_timed_context(doomslug(), 60 * u64::pow(10, 9)));
+        let signature = sign_attestation("doomslug.testnet", 650, 1_000, 2);
+        contract.store_signed_score(650, 1_000, 2, signature, pubkey);
+    }
+
+    #[test]
+    fn report_contract_version() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        assert_eq!("0.1.0", contract.contract_version());
+        assert_eq!("0.1.0", contract.read_state().version);
+
+        // events carry the version of the code that emitted them
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        assert_eq!("0.1.0", events_named("score_stored")[0]["contract_version"]);
+    }
+
+    #[test]
+    fn check_user_existence() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        assert!(!contract.user_exist("doomslug.testnet".to_string()));
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        assert!(contract.user_exist("doomslug.testnet".to_string()));
+        assert!(!contract.user_exist("rainbow.testnet".to_string()));
+
+        testing_env!(get_context(false, spensa()));
+        contract.delete_user("doomslug.testnet".to_string());
+        assert!(!contract.user_exist("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn query_scores_between_timestamps() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        for t in 0..4u64 {
+            testing_env!(get_timed_context(doomslug(), t * minute));
+            contract.store_score(500 + t as u16 * 10, "Score".to_string(), t + 1);
+        }
+
+        let account_id = "doomslug.testnet".to_string();
+        let middle: Vec<u16> = contract
+            .scores_between(account_id.clone(), minute, 2 * minute)
+            .iter()
+            .map(|u| u.score)
+            .collect();
+        assert_eq!(vec![510, 520], middle);
+        assert!(contract.scores_between(account_id, 5 * minute, 6 * minute).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_RANGE")]
+    fn reject_inverted_range() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        contract.scores_between("doomslug.testnet".to_string(), 2, 1);
+    }
+
+    #[test]
+    fn reset_contract_state() {
+        testing_env!(get_context(false, doomslug()));
+        let config = Config {
+            allow_reset: true,
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_context(false, rainbow()));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+
+        testing_env!(get_context(false, spensa()));
+        contract.store_score_for("nightshade.testnet".to_string(), 700, "Score of 700".to_string());
+
+        contract.reset();
+        assert_eq!(0, contract.read_state().user_count);
+        assert_eq!(0, contract.read_state().score_count);
+        assert!(!contract.user_exist("doomslug.testnet".to_string()));
+        assert!(contract.records.get(&"rainbow.testnet".to_string()).is_none());
+        assert_eq!(1, events_named("contract_reset").len());
+
+        // nothing about the wiped accounts survives the reset
+        assert!(contract.recent_activity(10).is_empty());
+        assert!(contract.nonces.get(&"doomslug.testnet".to_string()).is_none());
+        assert_eq!(0, contract.oracle_unique_accounts(spensa()));
+
+        // so a wiped account starts over, nonces included
+        testing_env!(get_context(false, doomslug()));
+        assert!(contract.store_score(550, "Score of 550".to_string(), 1).successful_operation);
+        assert_eq!(1, contract.recent_activity(10).len());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_RESET_DISABLED")]
+    fn reset_disabled_by_default() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.reset();
+    }
+
+    #[test]
+    fn keep_per_user_vectors_apart() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of doomslug".to_string(), 1);
+        testing_env!(get_context(false, rainbow()));
+        contract.store_score(600, "Score of rainbow".to_string(), 2);
+
+        // the two vectors live under different prefixes, none of which shadows another collection
+        let prefix = |account: &str| {
+            StorageKey::Accounts { account_hash: env::sha256(account.as_bytes()) }
+                .try_to_vec()
+                .unwrap()
+        };
+        let doomslug_prefix = prefix("doomslug.testnet");
+        let rainbow_prefix = prefix("rainbow.testnet");
+        assert_ne!(doomslug_prefix, rainbow_prefix);
+        assert_eq!(doomslug_prefix.len(), rainbow_prefix.len());
+        assert_ne!(b'm', doomslug_prefix[0]);
+        assert_ne!(StorageKey::UserMeta.try_to_vec().unwrap()[0], doomslug_prefix[0]);
+
+        // each history only holds its own user's score
+        let doomslug_history = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        let rainbow_history = contract.query_score_history("rainbow.testnet".to_string(), None, None, None);
+        assert_eq!(1, doomslug_history.len());
+        assert_eq!("Score of doomslug", doomslug_history.scores[0].description);
+        assert_eq!(1, rainbow_history.len());
+        assert_eq!("Score of rainbow", rainbow_history.scores[0].description);
+    }
+
+    #[test]
+    fn estimate_cost_of_storing_a_score() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        let new_user = contract.estimate_store_cost("doomslug.testnet".to_string());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        let returning_user = contract.estimate_store_cost("doomslug.testnet".to_string());
+
+        assert!(new_user.bytes > returning_user.bytes);
+        assert!(new_user.deposit.0 > returning_user.deposit.0);
+        assert_eq!(env::storage_byte_cost() * returning_user.bytes as u128, returning_user.deposit.0);
+    }
+
+    #[test]
+    fn rank_users_by_latest_score() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(600, "Score of 600".to_string(), 1);
+        testing_env!(get_context(false, spensa()));
+        contract.store_score(800, "Score of 800".to_string(), 2);
+        testing_env!(get_context(false, rainbow()));
+        contract.store_score(400, "Score of 400".to_string(), 3);
+
+        let ranking: Vec<String> = contract.leaderboard(10).into_iter().map(|a| a.account_id).collect();
+        assert_eq!(vec!["spensa.testnet", "doomslug.testnet", "rainbow.testnet"], ranking);
+        let top = contract.leaderboard(2);
+        assert_eq!(2, top.len());
+        assert_eq!(800, top[0].score);
+        assert_eq!(600, top[1].score);
+    }
+
+    #[test]
+    fn overwrite_user_history() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        for t in 0..3u64 {
+            testing_env!(get_timed_context(doomslug(), t * minute));
+            contract.store_score(500, "Score of 500".to_string(), t + 1);
+        }
+        testing_env!(get_timed_context(rainbow(), 0));
+        contract.store_score(600, "Score of 600".to_string(), 11);
+        assert_eq!(4, contract.read_state().score_count);
+
+        testing_env!(get_timed_context(spensa(), 5 * minute));
+        contract.overwrite_history(
+            "doomslug.testnet".to_string(),
+            vec![550, 650],
+            vec!["Recomputed 550".to_string(), "Recomputed 650".to_string()],
+        );
+        let history = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        assert_eq!(2, history.len());
+        assert_eq!(650, history.scores[1].score);
+        assert_eq!(5 * minute, history.scores[1].timestamp);
+        assert_eq!(3, contract.read_state().score_count);
+        assert_eq!(2, contract.read_state().user_count);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_LENGTH_MISMATCH")]
+    fn overwrite_history_with_mismatched_lengths() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.overwrite_history("doomslug.testnet".to_string(), vec![550, 650], vec!["Recomputed".to_string()]);
+    }
+
+    #[test]
+    fn validate_valid_score_input() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(spensa());
+        let result = contract.validate_score_input("doomslug.testnet".to_string(), 650, "Score of 650".to_string());
+        assert!(result.valid);
+        assert!(result.reason.is_none());
+    }
+
+    #[test]
+    fn validate_rejected_score_inputs() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let config = Config {
+            max_scores: 1,
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        let account_id = "doomslug.testnet".to_string();
+
+        let out_of_range = contract.validate_score_input(account_id.clone(), 950, "Score".to_string());
+        assert!(!out_of_range.valid);
+        assert!(out_of_range.reason.unwrap().contains("between 300 and 900"));
+        let too_long = contract.validate_score_input(account_id.clone(), 650, "a".repeat(281));
+        assert!(too_long.reason.unwrap().contains("at most 280 bytes"));
+
+        contract.store_score(650, "Score of 650".to_string(), 1);
+        let full = contract.validate_score_input(account_id.clone(), 650, "Score".to_string());
+        assert!(!full.valid);
+        assert!(full.reason.unwrap().contains("full"));
+
+        // with room left, the cooldown is what gets in the way
+        testing_env!(get_timed_context(spensa(), 0));
+        contract.set_user_cap(account_id.clone(), 10);
+        let too_recent = contract.validate_score_input(account_id.clone(), 650, "Score".to_string());
+        assert!(too_recent.reason.unwrap().contains("too recent"));
+        testing_env!(get_timed_context(doomslug(), minute));
+        assert!(contract.validate_score_input(account_id, 650, "Score".to_string()).valid);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_SCORE_OUT_OF_RANGE")]
+    fn store_score_outside_oracle_range() {
+        // the dry run and the write agree on the range
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        assert!(!contract.validate_score_input("doomslug.testnet".to_string(), 950, "Score".to_string()).valid);
+        contract.store_score(950, "Score of 950".to_string(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_SCORE_OUT_OF_RANGE")]
+    fn overwrite_history_outside_oracle_range() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.overwrite_history("doomslug.testnet".to_string(), vec![550, 950], vec!["Recomputed".to_string(); 2]);
+    }
+
+    #[test]
+    fn round_trip_raw_score_history() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+
+        let raw = contract.query_score_history_raw("doomslug.testnet".to_string());
+        let decoded = Vec::<User>::try_from_slice(&raw.0).unwrap();
+        let history = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        assert_eq!(history.len(), decoded.len());
+        for (u, s) in decoded.iter().zip(history.scores.iter()) {
+            assert_eq!(s.id, u.id);
+            assert_eq!(s.score, u.score);
+            assert_eq!(s.timestamp, u.timestamp);
+            assert_eq!(s.description.as_bytes(), &u.description[..]);
+        }
+    }
+
+    #[test]
+    fn accept_increasing_nonce() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        assert!(contract.store_score(500, "Score of 500".to_string(), 7).successful_operation);
+        testing_env!(get_timed_context(doomslug(), minute));
+        assert!(contract.store_score(600, "Score of 600".to_string(), 8).successful_operation);
+        assert_eq!(2, contract.query_score_history("doomslug.testnet".to_string(), None, None, None).len());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_STALE_NONCE")]
+    fn reject_repeated_nonce() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 7);
+        // a client retrying the very same submission later on
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(500, "Score of 500".to_string(), 7);
+    }
 
-        // ensure stats was incremented accordingly
-        let state1 = contract.read_state();
-        assert_eq!(1, state1.user_count, "ERR: should be 1 user");
-        assert_eq!(1, state1.score_count, "ERR: should be 1 score");
+    #[test]
+    fn get_owner_distinct_from_contract_account() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(rainbow());
+        assert_eq!(rainbow(), contract.get_owner());
+        assert_ne!(env::current_account_id(), contract.get_owner());
+        assert_eq!("rainbow.testnet", contract.read_state().owner);
+    }
 
-        // -------------- //
-        //      user 2    //
-        // -------------- //
-        // create a new context with a new predecessor for user #2: spensa
-        let context2 = get_context(false, spensa());
-        testing_env!(context2);
+    #[test]
+    fn normalize_latest_score() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(MIN_SCORE, "Bottom of the band".to_string(), 1);
+        assert_eq!(0, contract.query_latest_score_normalized("doomslug.testnet".to_string()));
 
-        // store second score
-        let msg2 = "Well done, your score is 501 points".to_string();
-        let out2 = contract.store_score(501, msg2);
-        assert!(out2.successful_operation);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(MAX_SCORE, "Top of the band".to_string(), 2);
+        assert_eq!(100, contract.query_latest_score_normalized("doomslug.testnet".to_string()));
 
-        // ensure again stats was incremented accordingly
-        assert_eq!("spensa.testnet".to_string(), out2.score_owner);
-        assert_eq!(2, contract.contract_state.user_count, "ERR: should be 2 users now");
-        assert_eq!(2, contract.contract_state.score_count, "ERR: should be 2 scores now");
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score((MIN_SCORE + MAX_SCORE) / 2, "Middle of the band".to_string(), 3);
+        assert_eq!(50, contract.query_latest_score_normalized("doomslug.testnet".to_string()));
+    }
 
-        // store third score
-        let msg3 = "You improved to 502 points".to_string();
-        let out3 = contract.store_score(502, msg3);
-        assert!(out3.successful_operation);
+    #[test]
+    fn check_existence_of_many_users() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_context(false, rainbow()));
+        contract.store_score(600, "Score of 600".to_string(), 1);
 
-        // check stats
-        assert_eq!("spensa.testnet".to_string(), out3.score_owner);
-        assert_eq!(2, contract.contract_state.user_count, "ERR: should remain 2 users");
-        assert_eq!(3, contract.contract_state.score_count, "ERR: expected 3 scores");
+        let accounts = vec![
+            "doomslug.testnet".to_string(),
+            "nobody.testnet".to_string(),
+            "rainbow.testnet".to_string(),
+            "spensa.testnet".to_string(),
+        ];
+        assert_eq!(vec![true, false, true, false], contract.users_exist(accounts));
+    }
 
-        // -------------- //
-        //      user 3    //
-        // -------------- //
-        // create a third context with a new predecessor for user #3: rainbow
-        let context3 = get_context(false, rainbow());
-        testing_env!(context3);
+    #[test]
+    #[should_panic(expected = "ERR_TOO_MANY_ACCOUNTS")]
+    fn reject_oversized_existence_check() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(spensa());
+        contract.users_exist(vec!["doomslug.testnet".to_string(); MAX_BATCH_SIZE + 1]);
+    }
 
-        // store a fourth, fifth, sixth score
-        contract.store_score(701, "Score of 701".to_string());
-        contract.store_score(702, "Score of 702".to_string());        
-        contract.store_score(703, "Score of 703".to_string());
+    #[test]
+    fn weight_recent_scores_more() {
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(300, "Score of 300".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), 28 * NS_PER_DAY));
+        contract.store_score(900, "Score of 900".to_string(), 2);
 
-        // check stats
-        assert_eq!(3, contract.contract_state.user_count, "ERR: expected 3 users");
-        assert_eq!(6, contract.contract_state.score_count, "ERR: expected 6 scores");
+        // the old score is four half-lives old, the recent one is brand new
+        let weighted = contract.query_weighted_score("doomslug.testnet".to_string(), 7);
+        assert!(weighted > 600 && weighted < 900);
+        assert_eq!(865, weighted);
+    }
 
-        // check on-chain score record
-        // .contains_key() returns true if the LookupMap 'records' contains a score record for a user
-        assert!(contract.records.contains_key(&"doomslug.testnet".to_string())); 
-        assert!(contract.records.contains_key(&"spensa.testnet".to_string()));
-        assert!(contract.records.contains_key(&"rainbow.testnet".to_string()));
-        assert!(!contract.records.contains_key(&"nightshade.testnet".to_string()));
+    #[test]
+    fn check_score_freshness() {
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        assert!(contract.is_score_fresh("doomslug.testnet".to_string(), 60));
 
-        // query all scores
-        let user1 = contract.query_score_history("doomslug.testnet".to_string());
-        let user2 = contract.query_score_history("spensa.testnet".to_string());
-        let user3 = contract.query_score_history("rainbow.testnet".to_string());
-        assert_eq!(1, user1.len(), "ERR: only 1 score for user 1");
-        assert_eq!(2, user2.len(), "ERR: expected 2 scores for user 2");
-        assert_eq!(3, user3.len(), "ERR: expected 3 scores for user 3");
+        // two minutes later the score is older than a minute
+        testing_env!(get_timed_context(doomslug(), 120 * u64::pow(10, 9)));
+        assert!(!contract.is_score_fresh("doomslug.testnet".to_string(), 60));
+        assert!(contract.is_score_fresh("doomslug.testnet".to_string(), 120));
 
-        // ensure nuemrical scores are stored under the right user
-        assert_ne!(user1.scores[0].score, user2.scores[1].score);
-        assert_ne!(user3.scores[1].score, user3.scores[2].score);
-        assert_ne!(user2.scores[1].score, user3.scores[2].score);
-        assert_eq!(502, user2.scores[1].score);
-        assert_eq!(703, user3.scores[2].score);
+        assert!(!contract.is_score_fresh("nobody.testnet".to_string(), 60));
     }
 
+    #[test]
+    fn count_only_active_users() {
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(rainbow(), 30 * NS_PER_DAY));
+        contract.store_score(600, "Score of 600".to_string(), 1);
+
+        // only rainbow scored within the past week
+        assert_eq!(2, contract.read_state().user_count);
+        assert_eq!(1, contract.active_user_count(7 * 86_400));
+    }
 
     #[test]
-    fn query_scores_and_state() {
-        let context = get_context(false, rainbow());
-        testing_env!(context);
+    fn amend_stored_description() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
         let mut contract = Contract::new(spensa());
-        let init_size = contract.read_state().size_now;
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Socre of 600".to_string(), 2);
 
-        // store 3 scores to blockchain first
-        let msg3 = "Score of 330";
-        contract.store_score(310, "Score of 310".to_string());
-        contract.store_score(320, "Score of 320".to_string());
-        let out = contract.store_score(330, msg3.to_string());
-        assert!(init_size < contract.read_state().size_now, "ERR: contract bytesize should increase when storing data");
+        testing_env!(get_timed_context(spensa(), 2 * minute));
+        contract.amend_description("doomslug.testnet".to_string(), 1, "Score of 600".to_string());
 
-        // query the 3 scores
-        let user0 = contract.query_score_history("rainbow.testnet".to_string());
-        assert_eq!(3, user0.scores.len(), "ERR: expected 3 scores");
-        assert!(!contract.records.get(&"rainbow.testnet".to_string()).is_none());
-        assert_eq!(320, user0.scores[1].score, "ERR: mismatchig scores");
-        assert_eq!(user0.scores[0].timestamp, user0.scores[1].timestamp, "ERR: expected identical timestamps");
-        assert_ne!(user0.scores[0].description, user0.scores[1].description, "ERR: expected different sha256 encryptions");
+        let amended = contract.query_score_at("doomslug.testnet".to_string(), 1);
+        assert_eq!(600, amended.score);
+        assert_eq!(minute, amended.timestamp);
+        assert!(contract.verify_description("doomslug.testnet".to_string(), 1, "Score of 600".to_string()));
+        assert!(!contract.verify_description("doomslug.testnet".to_string(), 1, "Socre of 600".to_string()));
+        // the other scores are left alone
+        assert!(contract.verify_description("doomslug.testnet".to_string(), 0, "Score of 500".to_string()));
+    }
 
-        // call read_state() to check stats
-        assert_eq!(1, contract.read_state().user_count, "ERR: expected 1 user");
-        assert_eq!(3, contract.read_state().score_count, "ERR: expected 3 scores");
-        assert!(contract.read_state().timestamp < 5 * u64::pow(10, 9), "ERR: contract should execute in < 5 sec");
-        assert!(contract.read_state().timestamp == env::block_timestamp(), "ERR: timestamp now should be identical to the contract timestamp");
+    #[test]
+    #[should_panic(expected = "ERR_INDEX_OUT_OF_BOUNDS")]
+    fn amend_description_out_of_range() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_context(false, spensa()));
+        contract.amend_description("doomslug.testnet".to_string(), 1, "Score of 500".to_string());
+    }
 
-        // ensure contract owner and score owner are correct
-        assert_eq!("rainbow.testnet".to_string(), out.score_owner, "ERR: mismatching score owners");
-        assert_eq!("spensa.testnet", contract.read_state().owner, "ERR: mismatching contract owners");
-        assert_eq!("spensa.testnet".to_string(), String::from(contract.owner_id), "ERR: mismatching contract owners");
+    #[test]
+    fn cached_average_tracks_history() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        for (k, score) in [500u16, 610, 720, 880].iter().enumerate() {
+            testing_env!(get_timed_context(doomslug(), k as u64 * minute));
+            contract.store_score(*score, format!("Score of {}", score), k as u64 + 1);
+        }
+        // the 880 was a mistake
+        contract.retract_latest_score();
+
+        let manual = contract
+            .query_score_history("doomslug.testnet".to_string(), None, None, None)
+            .scores
+            .iter()
+            .map(|s| s.score as u64)
+            .sum::<u64>()
+            / 3;
+        assert_eq!(610, manual);
+        assert_eq!(manual as u16, contract.cached_average("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn seed_counters_at_init() {
+        testing_env!(get_context(true, spensa()));
+        let contract = Contract::new_with_counters(spensa(), 12, 345);
+        let state = contract.read_state();
+        assert_eq!(12, state.user_count);
+        assert_eq!(345, state.score_count);
+    }
+
+    #[test]
+    fn merge_score_and_nft_count_into_status() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(720, "Score of 720".to_string(), 1);
+        contract.user_status("doomslug.testnet".to_string(), rainbow());
+        assert_eq!(2, get_created_receipts().len());
+
+        // mock the NFT contract reporting two tokens held by the user
+        testing_env!(
+            get_context(false, spensa()),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(serde_json::to_vec(&U128(2)).unwrap())],
+        );
+        let status = contract.on_user_status(720);
+        assert_eq!(720, status.score);
+        assert_eq!(2, status.nft_count.0);
+    }
+
+    // a context attaching the given deposit to the call
+    fn get_paying_context(predecessor: AccountId, deposit: u128) -> VMContext {
+        VMContextBuilder::new()
+            .current_account_id("spensa.testnet".to_string().try_into().unwrap())
+            .predecessor_account_id(predecessor)
+            .attached_deposit(deposit)
+            .build()
+    }
+
+    #[test]
+    fn store_score_with_sufficient_deposit() {
+        let min_deposit = 10u128.pow(22);
+        testing_env!(get_paying_context(doomslug(), 2 * min_deposit));
+        let config = Config {
+            min_deposit: U128(min_deposit),
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        assert!(contract.store_score(500, "Score of 500".to_string(), 1).successful_operation);
+
+        // the storage costs less than the minimum, so the minimum is kept and the rest refunded
+        assert_eq!(1, get_created_receipts().len());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DEPOSIT_BELOW_MINIMUM")]
+    fn reject_deposit_below_minimum() {
+        let min_deposit = 10u128.pow(22);
+        testing_env!(get_paying_context(doomslug(), min_deposit - 1));
+        let config = Config {
+            min_deposit: U128(min_deposit),
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        contract.store_score(500, "Score of 500".to_string(), 1);
+    }
+
+    #[test]
+    fn refund_deposit_without_minimum() {
+        // min_deposit is 0 by default
+        testing_env!(get_paying_context(doomslug(), 10u128.pow(24)));
+        let mut contract = Contract::new(spensa());
+        assert!(contract.store_score(500, "Score of 500".to_string(), 1).successful_operation);
+
+        // the write keeps its storage cost only, the rest goes back to the caller
+        let receipts = get_created_receipts();
+        assert_eq!(1, receipts.len());
+        assert_eq!(doomslug(), receipts[0].receiver_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DEPOSIT_BELOW_MINIMUM")]
+    fn reject_oracle_write_below_minimum() {
+        let min_deposit = 10u128.pow(22);
+        let config = Config {
+            min_deposit: U128(min_deposit),
+            ..Config::default()
+        };
+        testing_env!(get_paying_context(spensa(), 0));
+        let mut contract = Contract::new_with_config(spensa(), config);
+        // writing on behalf of another account is no way around the minimum
+        contract.store_score_for("doomslug.testnet".to_string(), 500, "Score of 500".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DEPOSIT_BELOW_MINIMUM")]
+    fn reject_signed_write_below_minimum() {
+        let min_deposit = 10u128.pow(22);
+        let config = Config {
+            min_deposit: U128(min_deposit),
+            ..Config::default()
+        };
+        testing_env!(get_paying_context(spensa(), 0));
+        let mut contract = Contract::new_with_config(spensa(), config);
+        let pubkey = Base64VecU8(attestation_keypair().public.to_bytes().to_vec());
+        contract.add_attestation_key(pubkey.clone());
+
+        testing_env!(get_paying_context(doomslug(), 0));
+        let signature = sign_attestation("doomslug.testnet", 720, 1_000, 1);
+        contract.store_signed_score(720, 1_000, 1, signature, pubkey);
+    }
+
+    #[test]
+    fn span_of_user_history() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), minute));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+        testing_env!(get_timed_context(doomslug(), 5 * minute));
+        contract.store_score(700, "Score of 700".to_string(), 3);
+
+        assert_eq!((minute, 5 * minute), contract.user_time_span("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn backfill_history_in_one_call() {
+        testing_env!(get_timed_context(spensa(), 10_000));
+        let mut contract = Contract::new(spensa());
+        let entries = vec![
+            (500, 1_000, "Score of 500".to_string()),
+            (550, 2_000, "Score of 550".to_string()),
+            (610, 2_001, "Score of 610".to_string()),
+        ];
+        contract.backfill_scores("doomslug.testnet".to_string(), entries);
+
+        let history = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        assert_eq!(3, history.len());
+        assert_eq!(550, history.scores[1].score);
+        assert_eq!(2_001, history.scores[2].timestamp);
+        assert_eq!(1_000, contract.user_since("doomslug.testnet".to_string()));
+        assert_eq!(1, contract.read_state().user_count);
+        assert_eq!(3, contract.read_state().score_count);
+        assert_eq!(553, contract.cached_average("doomslug.testnet".to_string()));
+    }
+
+    #[test]
+    fn backfill_history_older_than_stored_scores() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 10 * minute));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(700, "Score of 700".to_string(), 1);
+
+        testing_env!(get_timed_context(spensa(), 20 * minute));
+        let entries = vec![
+            (500, minute, "Score of 500".to_string()),
+            (600, 15 * minute, "Score of 600".to_string()),
+        ];
+        contract.backfill_scores("doomslug.testnet".to_string(), entries);
+
+        // the backfilled scores land around the stored one, in chronological order
+        let scores = contract.query_scores_only("doomslug.testnet".to_string());
+        assert_eq!(vec![(500, minute), (700, 10 * minute), (600, 15 * minute)], scores);
+        assert_eq!(minute, contract.user_since("doomslug.testnet".to_string()));
+        assert_eq!(600, contract.cached_average("doomslug.testnet".to_string()));
+        assert_eq!(3, contract.read_state().score_count);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_FUTURE_TIMESTAMP")]
+    fn reject_backfill_in_the_future() {
+        testing_env!(get_timed_context(spensa(), 10_000));
+        let mut contract = Contract::new(spensa());
+        let entries = vec![(500, 1_000, "Score of 500".to_string()), (550, 20_000, "Score of 550".to_string())];
+        contract.backfill_scores("doomslug.testnet".to_string(), entries);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NONMONOTONIC_TIMESTAMPS")]
+    fn reject_backfill_out_of_order() {
+        testing_env!(get_timed_context(spensa(), 10_000));
+        let mut contract = Contract::new(spensa());
+        let entries = vec![(500, 2_000, "Score of 500".to_string()), (550, 2_000, "Score of 550".to_string())];
+        contract.backfill_scores("doomslug.testnet".to_string(), entries);
+    }
+
+    #[test]
+    fn detect_full_history() {
+        testing_env!(get_context(false, spensa()));
+        let config = Config {
+            max_scores: 3,
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        let entries = vec![(500, 1_000, "Score of 500".to_string()), (550, 2_000, "Score of 550".to_string())];
+        contract.backfill_scores("doomslug.testnet".to_string(), entries);
+        assert!(!contract.is_at_cap("doomslug.testnet".to_string()));
+
+        contract.backfill_scores("doomslug.testnet".to_string(), vec![(600, 3_000, "Score of 600".to_string())]);
+        assert!(contract.is_at_cap("doomslug.testnet".to_string()));
+
+        assert!(!contract.is_at_cap("nobody.testnet".to_string()));
+    }
+
+    #[test]
+    fn pay_scores_from_storage_balance() {
+        let near = 10u128.pow(24);
+        testing_env!(get_paying_context(doomslug(), near));
+        let mut contract = Contract::new(spensa());
+        let registered = contract.storage_deposit(None, None);
+        assert_eq!(near, registered.total.0);
+
+        // the write draws its storage cost from the balance, no deposit attached
+        testing_env!(get_paying_context(doomslug(), 0));
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        let remaining = contract.storage_balance_of(doomslug()).unwrap().available.0;
+        assert!(remaining < near);
+
+        // withdraw what's left
+        testing_env!(get_paying_context(doomslug(), 1));
+        let withdrawn = contract.storage_withdraw(None);
+        assert_eq!(0, withdrawn.total.0);
+        assert_eq!(1, get_created_receipts().len());
+        assert_eq!(0, contract.storage_balance_of(doomslug()).unwrap().total.0);
+        assert!(contract.storage_balance_of(rainbow()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INSUFFICIENT_STORAGE_BALANCE")]
+    fn reject_score_exceeding_storage_balance() {
+        testing_env!(get_paying_context(doomslug(), 1));
+        let mut contract = Contract::new(spensa());
+        contract.storage_deposit(None, None);
+        testing_env!(get_paying_context(doomslug(), 0));
+        contract.store_score(500, "Score of 500".to_string(), 1);
+    }
+
+    #[test]
+    fn register_storage_balance_with_minimum_only() {
+        let near = 10u128.pow(24);
+        testing_env!(get_paying_context(doomslug(), near));
+        let mut contract = Contract::new(spensa());
+        let minimum = contract.estimate_store_cost("doomslug.testnet".to_string()).deposit.0;
+        let registered = contract.storage_deposit(None, Some(true));
+
+        // the new account keeps the minimum balance, the rest of the deposit goes back
+        assert_eq!(minimum, registered.total.0);
+        assert_eq!(1, get_created_receipts().len());
+    }
+
+    #[test]
+    fn pay_oracle_and_minting_writes_from_storage_balance() {
+        let near = 10u128.pow(24);
+        testing_env!(get_paying_context(spensa(), near));
+        let config = Config {
+            nft_contract: Some(rainbow()),
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        contract.storage_deposit(None, None);
+        testing_env!(get_paying_context(spensa(), 0));
+        contract.store_score_for("doomslug.testnet".to_string(), 500, "Score of 500".to_string());
+        assert!(contract.storage_balance_of(spensa()).unwrap().total.0 < near);
+
+        // the whole attached deposit goes to the NFT contract, the score is paid from the balance
+        testing_env!(get_paying_context(doomslug(), near));
+        contract.storage_deposit(None, None);
+        testing_env!(get_paying_context(doomslug(), near / 10));
+        contract.store_score_and_mint(720, "Score of 720".to_string(), "score-1".to_string());
+        assert!(contract.storage_balance_of(doomslug()).unwrap().total.0 < near);
+    }
+
+    #[test]
+    fn emit_score_delta_for_returning_user() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(640, "Score of 640".to_string(), 1);
+        // a first write has nothing to compare against
+        assert!(events_named("score_updated").is_empty());
+
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(590, "Score of 590".to_string(), 2);
+        let updated = events_named("score_updated");
+        assert_eq!(1, updated.len());
+        assert_eq!(640, updated[0]["data"][0]["previous_score"]);
+        assert_eq!(590, updated[0]["data"][0]["new_score"]);
+        assert_eq!(-50, updated[0]["data"][0]["delta"]);
+        // the update stands in for score_stored, so indexers see exactly one event per write
+        assert!(events_named("score_stored").is_empty());
+    }
+
+    #[test]
+    fn second_owner_administers_contract() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.add_owner(rainbow());
+        assert_eq!(vec![spensa(), rainbow()], contract.get_owners());
+
+        // the new owner can call owner-gated methods
+        testing_env!(get_context(false, rainbow()));
+        contract.set_user_cap("doomslug.testnet".to_string(), 10);
+        assert_eq!(10, contract.get_user_cap("doomslug.testnet".to_string()));
+
+        // and even revoke the original owner
+        contract.remove_owner(spensa());
+        assert_eq!(vec![rainbow()], contract.get_owners());
+        assert_eq!(rainbow(), contract.get_owner());
+    }
+
+    #[test]
+    fn primary_owner_survives_revoking_other_owners() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.add_owner(rainbow());
+        contract.add_owner(doomslug());
+        contract.remove_owner(rainbow());
+        assert_eq!(spensa(), contract.get_owner());
+        assert_eq!("spensa.testnet", contract.read_state().owner);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CANNOT_REMOVE_PRIMARY_OWNER")]
+    fn primary_owner_cannot_revoke_itself() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.add_owner(rainbow());
+        contract.remove_owner(spensa());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CANNOT_REMOVE_LAST_OWNER")]
+    fn keep_last_owner() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        contract.remove_owner(spensa());
+    }
+
+    #[test]
+    fn hash_account_id_for_indexers() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(spensa());
+        let hash = contract.account_hash("doomslug.testnet".to_string());
+        assert_eq!(32, hash.0.len());
+        assert_eq!(
+            "\"oaKohw+Ay7srDqT9nmgosutYRcsHtPjINcjA7pjXp1E=\"",
+            serde_json::to_string(&hash).unwrap()
+        );
+    }
+
+    #[test]
+    fn project_scores_and_timestamps() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+
+        let pairs = contract.query_scores_only("doomslug.testnet".to_string());
+        let history = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        assert_eq!(history.len(), pairs.len());
+        for (pair, s) in pairs.iter().zip(history.scores.iter()) {
+            assert_eq!((s.score, s.timestamp), *pair);
+        }
+        assert_eq!(vec![(500, 0), (600, minute)], pairs);
+    }
+
+    #[test]
+    fn filter_scores_by_issuer() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(spensa(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.add_oracle(rainbow());
+        testing_env!(get_timed_context(doomslug(), 0));
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(rainbow(), minute));
+        contract.store_score_for("doomslug.testnet".to_string(), 600, "Score of 600".to_string());
+
+        let by_oracle = contract.query_scores_by_issuer("doomslug.testnet".to_string(), rainbow());
+        assert_eq!(1, by_oracle.len());
+        assert_eq!(600, by_oracle[0].score);
+        let self_issued = contract.query_scores_by_issuer("doomslug.testnet".to_string(), doomslug());
+        assert_eq!(1, self_issued.len());
+        assert_eq!(500, self_issued[0].score);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn healthy_contract() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 2);
+        testing_env!(get_timed_context(rainbow(), minute));
+        contract.store_score(700, "Score of 700".to_string(), 1);
+
+        let report = contract.health_check();
+        assert!(report.ok);
+        assert!(report.violations.is_empty());
+    }
+
+    #[test]
+    fn report_counters_out_of_sync() {
+        testing_env!(get_context(true, spensa()));
+        // seeded counters without the imported data
+        let contract = Contract::new_with_counters(spensa(), 3, 2);
+        let report = contract.health_check();
+        assert!(!report.ok);
+        assert_eq!(2, report.violations.len());
+    }
+
+    #[test]
+    fn feed_recent_activity_newest_first() {
+        let minute = 60 * u64::pow(10, 9);
+        testing_env!(get_timed_context(doomslug(), 0));
+        let mut contract = Contract::new(spensa());
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        testing_env!(get_timed_context(rainbow(), minute));
+        contract.store_score(600, "Score of 600".to_string(), 1);
+        testing_env!(get_timed_context(doomslug(), 2 * minute));
+        contract.store_score(700, "Score of 700".to_string(), 2);
+        testing_env!(get_timed_context(rainbow(), 3 * minute));
+        contract.store_score(800, "Score of 800".to_string(), 2);
+
+        let feed = contract.recent_activity(10);
+        let scores: Vec<u16> = feed.iter().map(|r| r.score).collect();
+        assert_eq!(vec![800, 700, 600, 500], scores);
+        assert_eq!("rainbow.testnet", feed[0].account_id);
+        assert_eq!(3 * minute, feed[0].timestamp);
+        assert_eq!(2, contract.recent_activity(2).len());
+    }
+
+    #[test]
+    fn emit_events_under_custom_standard() {
+        testing_env!(get_context(false, doomslug()));
+        let config = Config {
+            event_standard: "acmecredit".to_string(),
+            ..Config::default()
+        };
+        let mut contract = Contract::new_with_config(spensa(), config);
+        contract.store_score(500, "Score of 500".to_string(), 1);
+        assert_eq!("acmecredit", events_named("score_stored")[0]["standard"]);
+    }
+
+    #[test]
+    fn empty_history_for_missing_account() {
+        testing_env!(get_context(true, doomslug()));
+        let contract = Contract::new(spensa());
+        let history = contract.query_score_history("nobody.testnet".to_string(), None, None, None);
+        assert_eq!(0, history.len());
+    }
+
+    #[test]
+    fn empty_history_for_emptied_account() {
+        testing_env!(get_context(false, doomslug()));
+        let mut contract = Contract::new(spensa());
+        let emptied: Vector<User> =
+            Vector::new(StorageKey::Accounts { account_hash: env::sha256(b"doomslug.testnet") });
+        contract.records.insert(&"doomslug.testnet".to_string(), &emptied);
+        let history = contract.query_score_history("doomslug.testnet".to_string(), None, None, None);
+        assert_eq!(0, history.len());
+    }
+
+    #[test]
+    fn rank_latest_score_among_users() {
+        testing_env!(get_context(false, spensa()));
+        let mut contract = Contract::new(spensa());
+        for (user, score) in [(doomslug(), 300u16), (rainbow(), 600), (spensa(), 900)].iter() {
+            testing_env!(get_context(false, user.clone()));
+            contract.store_score(*score, format!("Score of {}", score), 1);
+        }
+
+        assert_eq!(50, contract.score_percentile("rainbow.testnet".to_string()));
+        assert!(contract.score_percentile("doomslug.testnet".to_string()) < 50);
+        assert!(contract.score_percentile("spensa.testnet".to_string()) > 50);
+    }
+}